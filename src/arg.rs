@@ -0,0 +1,167 @@
+use std::fmt::{self, Write};
+
+use crate::attributes::Attributes;
+use crate::formatter::Formatter;
+use crate::pat::Pat;
+use crate::type_def::Type;
+use crate::visit::{Text, Visit};
+
+use crate::impl_macros::impl_attr_methods;
+
+
+/// Defines a function argument, like `(a, b): (u8, u8)` or
+/// `#[cfg(unix)] fd: i32`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arg {
+    /// Argument pattern
+    pat: Pat,
+    /// Argument type
+    ty: Type,
+    /// Argument attributes, e.g. `#[cfg(unix)]`
+    attrs: Attributes,
+}
+
+/// The `self` argument of a method.
+///
+/// ```
+/// use codegen::Receiver;
+///
+/// let receiver = Receiver::Ref { lifetime: Some("'a".to_string()), mutable: true };
+/// assert_eq!(receiver.to_string(), "&'a mut self");
+/// assert_eq!(Receiver::typed("Pin<&mut Self>").to_string(), "self: Pin<&mut Self>");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Receiver {
+    /// `self` or `mut self`.
+    Value {
+        /// Whether or not the binding is `mut`.
+        mutable: bool,
+    },
+    /// `&self`, `&'a self` or `&mut self`.
+    Ref {
+        /// The lifetime of the reference, if any.
+        lifetime: Option<String>,
+        /// Whether or not the reference is mutable.
+        mutable: bool,
+    },
+    /// `self` with an explicit type, like `self: Box<Self>` or
+    /// `mut self: Pin<&mut Self>`.
+    Typed {
+        /// Whether or not the binding is `mut`.
+        mutable: bool,
+        /// The type of `self`.
+        ty: Type,
+    },
+}
+
+impl Arg {
+    /// Return an argument binding `pat` to a value of type `ty`.
+    pub fn new<P, T>(pat: P, ty: T) -> Self
+    where
+        P: Into<Pat>,
+        T: Into<Type>,
+    {
+        Arg {
+            pat: pat.into(),
+            ty: ty.into(),
+            attrs: Attributes::default(),
+        }
+    }
+
+    /// Returns the argument pattern.
+    pub fn pat(&self) -> &Pat {
+        &self.pat
+    }
+
+    /// Returns the argument type.
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// Formats the argument using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.attrs.fmt_attrs_inline(fmt)?;
+        self.pat.fmt_nested(fmt)?;
+        write!(fmt, ": ")?;
+        self.ty.fmt(fmt)
+    }
+
+    impl_attr_methods!(attrs);
+}
+
+impl Receiver {
+    /// Returns a `self` receiver with an explicit type, like
+    /// `self: Box<Self>`.
+    pub fn typed<T>(ty: T) -> Self
+    where
+        T: Into<Type>,
+    {
+        Receiver::Typed { mutable: false, ty: ty.into() }
+    }
+
+    /// Formats the receiver using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Receiver::Value { mutable } | Receiver::Typed { mutable, .. } if *mutable => {
+                write!(fmt, "mut ")?;
+            }
+            Receiver::Ref { lifetime, mutable } => {
+                write!(fmt, "&")?;
+
+                if let Some(lifetime) = lifetime {
+                    write!(fmt, "{} ", lifetime)?;
+                }
+
+                if *mutable {
+                    write!(fmt, "mut ")?;
+                }
+            }
+            _ => {}
+        }
+
+        write!(fmt, "self")?;
+
+        if let Receiver::Typed { ty, .. } = self {
+            write!(fmt, ": ")?;
+            ty.fmt(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Receiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        Receiver::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
+
+impl Visit for Arg {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        f(&self.ty);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        f(&mut self.ty);
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+    }
+}
+
+impl Visit for Receiver {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        if let Receiver::Typed { ty, .. } = self {
+            f(ty);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        if let Receiver::Typed { ty, .. } = self {
+            f(ty);
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use std::fmt::{self, Write};
 
 
-use crate::bounds::{Bound, BoundEndsWith};
+use crate::bounds::Bound;
 use crate::docs::Docs;
 use crate::formatter::Formatter;
+use crate::visit::Visit;
 
-use crate::type_def::Type;
+use crate::type_def::{Type, TypeBound};
 
 use crate::impl_macros::{
     impl_bound_methods,
@@ -13,7 +14,7 @@ use crate::impl_macros::{
 };
 
 /// Defines an associated type.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AssociatedType {
     bound: Bound,
     docs: Docs,
@@ -35,7 +36,7 @@ impl AssociatedType {
     pub fn new_with_bound<S, T>(name: S, bound: T) -> Self
     where
         S: AsRef<str>,
-        T: Into<Type>
+        T: Into<TypeBound>
     {
         Self {
             docs: Docs::default(),
@@ -43,11 +44,27 @@ impl AssociatedType {
         }
     }
 
+    /// Returns the name of the associated type.
+    pub(crate) fn name(&self) -> &str {
+        self.bound.name()
+    }
+
     pub(crate) fn fmt_assoc_type(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         write!(formatter, "type ")?;
-        self.bound.fmt_bound(formatter, BoundEndsWith::SemiColon)
+        self.bound.fmt_bound(formatter)?;
+        writeln!(formatter, ";")
     }
 
     impl_bound_methods!(bound);
     impl_doc_methods!(docs);
 }
+
+impl Visit for AssociatedType {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.bound.visit_types(f);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.bound.visit_types_mut(f);
+    }
+}
@@ -1,6 +1,8 @@
 use std::fmt::{self, Write};
 
 use crate::formatter::Formatter;
+use crate::type_def::Type;
+use crate::visit::{Text, Visit};
 
 /// A container for attributes.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -105,23 +107,39 @@ impl Attributes {
     /// wrapping '#[...]' brackets, they'll be added here.
     pub(crate) fn fmt_attrs(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         for attr in self.attrs.iter() {
-            if !attr.starts_with("#[") {
-                write!(formatter, "#[")?;
-            }
-
-            write!(formatter, "{}", attr)?;
+            fmt_attr(attr, formatter)?;
+            writeln!(formatter)?;
+        }
 
-            if !attr.ends_with("]") {
-                write!(formatter, "]")?;
-            }
+        Ok(())
+    }
 
-            write!(formatter, "\n")?;
+    /// Write out the attributes on the same line as the item they apply to,
+    /// like those of a function argument.
+    pub(crate) fn fmt_attrs_inline(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        for attr in self.attrs.iter() {
+            fmt_attr(attr, formatter)?;
+            write!(formatter, " ")?;
         }
 
         Ok(())
     }
 }
 
+fn fmt_attr(attr: &str, formatter: &mut Formatter<'_>) -> fmt::Result {
+    if !attr.starts_with("#[") {
+        write!(formatter, "#[")?;
+    }
+
+    write!(formatter, "{}", attr)?;
+
+    if !attr.ends_with("]") {
+        write!(formatter, "]")?;
+    }
+
+    Ok(())
+}
+
 impl Default for Attributes {
     fn default() -> Self {
         Self::new()
@@ -146,7 +164,7 @@ where
 ///
 /// ## Example:
 ///
-/// ```
+/// ```ignore
 /// # #[macro_use]
 /// # extern crate codegen;
 /// # use codegen::attributes::Attributes;
@@ -246,3 +264,15 @@ macro_rules! impl_attr_methods {
 }
 
 pub(crate) use impl_attr_methods;
+
+impl Visit for Attributes {
+    fn visit_types(&self, _: &mut dyn FnMut(&Type)) {}
+
+    fn visit_types_mut(&mut self, _: &mut dyn FnMut(&mut Type)) {}
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        for attr in &self.attrs {
+            f(Text::Attr, attr);
+        }
+    }
+}
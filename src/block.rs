@@ -1,14 +1,19 @@
 use std::fmt::{self, Write};
 
-use crate::body::Body;
+use crate::expr::Expr;
 use crate::formatter::Formatter;
+use crate::item::Item;
+use crate::pat::Pat;
+use crate::stmt::Stmt;
+use crate::type_def::Type;
+use crate::visit::{Text, Visit};
 
 /// Defines a code block. This is used to define a function body.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Block {
     before: Option<String>,
     after: Option<String>,
-    body: Vec<Body>,
+    body: Vec<Stmt>,
 }
 
 impl Block {
@@ -21,21 +26,86 @@ impl Block {
         }
     }
 
+    /// Returns an empty `unsafe { .. }` block.
+    pub fn new_unsafe() -> Self {
+        Self::new("unsafe")
+    }
+
+    /// Returns an empty `async { .. }` block, or `async move { .. }` when
+    /// `is_move` is set.
+    pub fn new_async(is_move: bool) -> Self {
+        Self::new(if is_move { "async move" } else { "async" })
+    }
+
     /// Push a line to the code block.
     pub fn line<T>(&mut self, line: T) -> &mut Self
     where
         T: ToString,
     {
-        self.body.push(Body::String(line.to_string()));
+        self.body.push(Stmt::Verbatim(line.to_string()));
+        self
+    }
+
+    /// Push an expression to the code block, as a line of its own.
+    pub fn push_expr<E>(&mut self, expr: E) -> &mut Self
+    where
+        E: Into<Expr>,
+    {
+        self.body.push(Stmt::Expr(expr.into()));
         self
     }
 
+    /// Push a `let pat = init else { .. };` statement to the code block, and
+    /// return the `else` block.
+    pub fn new_let_else<P, E>(&mut self, pat: P, init: E) -> &mut Block
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        self.body.push(Stmt::let_else(pat, init, Block::new("")));
+
+        match *self.body.last_mut().unwrap() {
+            Stmt::LetElse { ref mut else_block, .. } => else_block,
+            _ => unreachable!(),
+        }
+    }
+
     /// Push a nested block to this block.
     pub fn push_block(&mut self, block: Block) -> &mut Self {
-        self.body.push(Body::Block(block));
+        self.body.push(Stmt::Expr(Expr::Block(block)));
         self
     }
 
+    /// Push a statement to the code block.
+    pub fn push_stmt(&mut self, stmt: Stmt) -> &mut Self {
+        self.body.push(stmt);
+        self
+    }
+
+    /// Insert a statement into the code block at `index`, shifting the
+    /// statements after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of statements.
+    pub fn insert_stmt(&mut self, index: usize, stmt: Stmt) -> &mut Self {
+        self.body.insert(index, stmt);
+        self
+    }
+
+    /// Push a nested item, like a helper `fn`, to the code block.
+    pub fn push_item<I>(&mut self, item: I) -> &mut Self
+    where
+        I: Into<Item>,
+    {
+        self.push_stmt(Stmt::Item(item.into()))
+    }
+
+    /// Returns the statements of the code block.
+    pub fn stmts(&self) -> &[Stmt] {
+        &self.body
+    }
+
     /// Add a snippet after the block.
     pub fn after(&mut self, after: &str) -> &mut Self {
         self.after = Some(after.to_string());
@@ -54,7 +124,7 @@ impl Block {
             write!(fmt, " ")?;
         }
 
-        write!(fmt, "{{\n")?;
+        writeln!(fmt, "{{")?;
 
         fmt.indent(|fmt| {
             for b in &self.body {
@@ -70,7 +140,76 @@ impl Block {
             write!(fmt, "{}", after)?;
         }
 
-        write!(fmt, "\n")?;
+        writeln!(fmt)?;
+        Ok(())
+    }
+
+    /// Formats the block as an expression, without a trailing newline.
+    pub(crate) fn fmt_expr(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(before) = self.before.as_deref().filter(|before| !before.is_empty()) {
+            write!(fmt, "{} ", before)?;
+        }
+
+        self.fmt_braces(fmt)?;
+
+        if let Some(ref after) = self.after {
+            write!(fmt, "{}", after)?;
+        }
+
         Ok(())
     }
+
+    /// Formats only the braces and contents of the block, for blocks owned
+    /// by control flow expressions.
+    pub(crate) fn fmt_braces(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "{{")?;
+
+        fmt.indent(|fmt| {
+            for b in &self.body {
+                b.fmt(fmt)?;
+            }
+
+            Ok(())
+        })?;
+
+        write!(fmt, "}}")
+    }
+
+    /// Whether the block holds any source text that is written as is.
+    pub(crate) fn has_verbatim(&self) -> bool {
+        self.before.iter().chain(&self.after).any(|text| !text.is_empty())
+            || self.body.iter().any(Stmt::has_verbatim)
+    }
+}
+
+impl Visit for Block {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        for body in &self.body {
+            body.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        for body in &mut self.body {
+            body.visit_types_mut(f);
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        for text in self.before.iter().chain(&self.after) {
+            if !text.is_empty() {
+                f(Text::Verbatim, text);
+            }
+        }
+
+        for body in &self.body {
+            body.visit_text(f);
+        }
+    }
+
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        for body in &self.body {
+            body.visit_items(f);
+        }
+    }
 }
@@ -1,21 +1,51 @@
 use std::fmt::{self, Write};
 
 use crate::formatter::Formatter;
+use crate::parse::Parser;
+use crate::visit::Visit;
 
-use crate::type_def::Type;
+use crate::type_def::{Type, TypeBound, fmt_for_lifetimes};
 
 /// Represents a collection of bounds for a single type.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Bound {
     name: String,
-    bounds: Vec<Type>,
+    bounds: Vec<TypeBound>,
 }
 
-/// Formatting helper for chaining bounds in different scenarios.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum BoundEndsWith {
-    SemiColon,
-    Comma,
+/// A predicate of a `where` clause.
+///
+/// ```
+/// use codegen::{TypeBound, WherePredicate};
+///
+/// let pred = WherePredicate::new("T")
+///     .for_lifetime("'de")
+///     .bound("Deserialize<'de>")
+///     .bound(TypeBound::maybe("Sized"));
+/// assert_eq!(pred.to_string(), "for<'de> T: Deserialize<'de> + ?Sized");
+///
+/// let pred = WherePredicate::from("<T as Iterator>::Item: Send");
+/// assert_eq!(pred, WherePredicate::new("<T as Iterator>::Item").bound("Send"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WherePredicate {
+    /// Bounds on a type, e.g. `for<'de> T: Deserialize<'de>` or
+    /// `<T as Trait>::Assoc: Send`.
+    Type {
+        /// The lifetimes introduced by `for<..>`.
+        for_lifetimes: Vec<String>,
+        /// The bounded type.
+        ty: Type,
+        /// The bounds on the type.
+        bounds: Vec<TypeBound>,
+    },
+    /// Bounds on a lifetime, e.g. `'a: 'b + 'c`.
+    Lifetime {
+        /// The bounded lifetime.
+        lifetime: String,
+        /// The lifetimes that it outlives.
+        outlives: Vec<String>,
+    },
 }
 
 impl Bound {
@@ -35,7 +65,7 @@ impl Bound {
     where
         S: AsRef<str>,
         I: IntoIterator<Item = T>,
-        T: Into<Type>,
+        T: Into<TypeBound>,
     {
         Self {
             name: name.as_ref().to_owned(),
@@ -47,7 +77,7 @@ impl Bound {
     pub fn new_with_bound<S, T>(name: S, bound: T) -> Self
     where
         S: AsRef<str>,
-        T: Into<Type>,
+        T: Into<TypeBound>,
     {
         Self::new_with_bounds(name, vec![bound.into()])
     }
@@ -59,7 +89,7 @@ impl Bound {
 
 
     /// Returns a slice of the bounding types
-    pub fn bounds(&self) -> &[Type] {
+    pub fn bounds(&self) -> &[TypeBound] {
         self.bounds.as_slice()
     }
 
@@ -70,7 +100,7 @@ impl Bound {
 
     /// Checks if this bound has type/trait requirements.
     pub fn has_inner_bounds(&self) -> bool {
-        self.bounds.is_empty()
+        !self.bounds.is_empty()
     }
 
     /// Clears all bound requirements
@@ -82,7 +112,7 @@ impl Bound {
     /// Pushes a single bound requirement
     pub fn push_bound<T>(&mut self, bound: T) -> &mut Self
     where
-        T: Into<Type>
+        T: Into<TypeBound>
     {
         self.bounds.push(bound.into());
         self
@@ -92,108 +122,252 @@ impl Bound {
     pub fn extend_bounds<I, T>(&mut self, bounds: I) -> &mut Self
     where
         I: IntoIterator<Item = T>,
-        T: Into<Type>,
+        T: Into<TypeBound>,
     {
         self.bounds.extend(bounds.into_iter().map(|b| b.into()));
         self
     }
 
-    pub(crate) fn fmt_bound(
-        &self,
-        formatter: &mut Formatter<'_>,
-        ends_with: BoundEndsWith,
-    ) -> fmt::Result {
-        if self.bounds.is_empty() {
-            write!(formatter, "{}", self.name)?;
+    /// Formats the name and its bounds, e.g. `Item: Clone + Send`.
+    pub(crate) fn fmt_bound(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.name)?;
+        fmt_bound_list(&self.bounds, formatter)
+    }
+}
+
+impl<S, I, T> From<(S, I)> for Bound
+where
+    S: AsRef<str>,
+    I: IntoIterator<Item = T>,
+    T: Into<TypeBound>,
+{
+    fn from(bound_tup: (S, I)) -> Self {
+        Self::new_with_bounds(bound_tup.0, bound_tup.1)
+    }
+}
+
+impl WherePredicate {
+    /// Returns a predicate on the given type, without bounds.
+    pub fn new<T>(ty: T) -> Self
+    where
+        T: Into<Type>,
+    {
+        WherePredicate::Type {
+            for_lifetimes: vec![],
+            ty: ty.into(),
+            bounds: vec![],
+        }
+    }
+
+    /// Returns a predicate on the given lifetime, without bounds.
+    pub fn lifetime<S>(lifetime: S) -> Self
+    where
+        S: ToString,
+    {
+        WherePredicate::Lifetime {
+            lifetime: lifetime.to_string(),
+            outlives: vec![],
+        }
+    }
+
+    /// Add a lifetime to the `for<..>` binder of a type predicate. Has no
+    /// effect on lifetime predicates.
+    pub fn for_lifetime<S>(mut self, lifetime: S) -> Self
+    where
+        S: ToString,
+    {
+        if let WherePredicate::Type { ref mut for_lifetimes, .. } = self {
+            for_lifetimes.push(lifetime.to_string());
+        }
+        self
+    }
+
+    /// Add a bound to a type predicate. Has no effect on lifetime
+    /// predicates.
+    pub fn bound<B>(mut self, bound: B) -> Self
+    where
+        B: Into<TypeBound>,
+    {
+        if let WherePredicate::Type { ref mut bounds, .. } = self {
+            bounds.push(bound.into());
+        }
+        self
+    }
+
+    /// Add a lifetime that a lifetime predicate outlives. Has no effect on
+    /// type predicates.
+    pub fn outlives<S>(mut self, lifetime: S) -> Self
+    where
+        S: ToString,
+    {
+        if let WherePredicate::Lifetime { ref mut outlives, .. } = self {
+            outlives.push(lifetime.to_string());
         }
-        else {
-            write!(formatter, "{}:", self.name)?;
+        self
+    }
 
-            for (i, ty) in self.bounds.iter().enumerate() {
-                if i != 0 {
-                    write!(formatter, " + ")?
+    /// Formats the predicate using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WherePredicate::Type { for_lifetimes, ty, bounds } => {
+                if !for_lifetimes.is_empty() {
+                    fmt_for_lifetimes(for_lifetimes, fmt)?;
                 }
-                ty.fmt(formatter)?;
+
+                ty.fmt(fmt)?;
+                fmt_bound_list(bounds, fmt)
             }
-        }
+            WherePredicate::Lifetime { lifetime, outlives } => {
+                write!(fmt, "{}:", lifetime)?;
+
+                for (i, lifetime) in outlives.iter().enumerate() {
+                    write!(fmt, "{}{}", if i == 0 { " " } else { " + " }, lifetime)?;
+                }
 
-        match ends_with {
-            BoundEndsWith::SemiColon => {
-                write!(formatter, ";\n")?;
-            },
-            BoundEndsWith::Comma => {
-                write!(formatter, ",\n")?;
+                Ok(())
             }
         }
+    }
+}
 
-        Ok(())
+/// Writes `: A + B` for a non-empty list of bounds.
+fn fmt_bound_list(bounds: &[TypeBound], fmt: &mut Formatter<'_>) -> fmt::Result {
+    for (i, bound) in bounds.iter().enumerate() {
+        write!(fmt, "{}", if i == 0 { ": " } else { " + " })?;
+        bound.fmt(fmt)?;
     }
+
+    Ok(())
 }
 
-impl<S, I, T> From<(S, I)> for Bound
+impl fmt::Display for WherePredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        WherePredicate::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
+
+impl<S> From<S> for WherePredicate
 where
-    S: AsRef<str>,
-    I: IntoIterator<Item = T>,
-    T: Into<Type>,
+    S: AsRef<str>
 {
-    fn from(bound_tup: (S, I)) -> Self {
-        Self::new_with_bounds(bound_tup.0, bound_tup.1)
+    fn from(src: S) -> Self {
+        let src = src.as_ref();
+
+        Parser::new(src)
+            .and_then(|mut parser| {
+                let pred = parser.parse_where_predicate()?;
+                parser.finish()?;
+                Ok(pred)
+            })
+            .unwrap_or_else(|_| WherePredicate::new(Type::Verbatim(src.to_owned())))
+    }
+}
+
+impl From<Bound> for WherePredicate {
+    fn from(src: Bound) -> Self {
+        if src.name.starts_with('\'') {
+            let outlives = src.bounds.into_iter()
+                .map(|bound| match bound {
+                    TypeBound::Lifetime(lifetime) => lifetime,
+                    bound => bound.to_string(),
+                })
+                .collect();
+
+            return WherePredicate::Lifetime { lifetime: src.name, outlives };
+        }
+
+        WherePredicate::Type {
+            for_lifetimes: vec![],
+            ty: Type::new(src.name),
+            bounds: src.bounds,
+        }
     }
 }
 
+impl<'a> From<&'a WherePredicate> for WherePredicate {
+    fn from(src: &'a WherePredicate) -> Self {
+        src.clone()
+    }
+}
+
+/// A collection of bounds, formatted as a `where` clause.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Bounds {
-    bounds: Vec<Bound>,
+    bounds: Vec<WherePredicate>,
 }
 
 impl Bounds {
+    /// Creates an empty collection of bounds.
     pub fn new() -> Self {
         Self { bounds: vec![] }
     }
 
-    pub fn new_with_bound(bound: Bound) -> Self {
-        Self { bounds: vec![bound] }
+    /// Creates a collection with a single bound.
+    pub fn new_with_bound<P>(bound: P) -> Self
+    where
+        P: Into<WherePredicate>
+    {
+        Self { bounds: vec![bound.into()] }
     }
 
-    pub fn new_with_bounds<I>(bounds: I) -> Self
+    /// Creates a collection from an iterator of bounds.
+    pub fn new_with_bounds<I, P>(bounds: I) -> Self
     where
-        I: IntoIterator<Item = Bound>
+        I: IntoIterator<Item = P>,
+        P: Into<WherePredicate>
     {
-        Self { bounds: bounds.into_iter().collect() }
+        Self { bounds: bounds.into_iter().map(Into::into).collect() }
     }
 
+    /// Returns the number of bounds in the collection.
     pub fn bound_count(&self) -> usize {
         self.bounds.len()
     }
 
+    /// Whether or not this has any bounds.
     pub fn has_bounds(&self) -> bool {
-        self.bounds.is_empty()
+        !self.bounds.is_empty()
     }
 
+    /// Returns the predicates of the `where` clause.
+    pub fn predicates(&self) -> &[WherePredicate] {
+        &self.bounds
+    }
+
+    /// Clears all bounds.
     pub fn clear_bounds(&mut self) -> &mut Self {
         self.bounds.clear();
         self
     }
 
-    pub fn push_bound(&mut self, bound: Bound) -> &mut Self {
-        self.bounds.push(bound);
+    /// Pushes a single bound, like a [`Bound`] or a `"T: ?Sized"` string.
+    pub fn push_bound<P>(&mut self, bound: P) -> &mut Self
+    where
+        P: Into<WherePredicate>
+    {
+        self.bounds.push(bound.into());
         self
     }
 
-    pub fn extend_bounds<I>(&mut self, bounds: I) -> &mut Self
+    /// Extends the collection with more bounds.
+    pub fn extend_bounds<I, P>(&mut self, bounds: I) -> &mut Self
     where
-        I: IntoIterator<Item = Bound>
+        I: IntoIterator<Item = P>,
+        P: Into<WherePredicate>
     {
-        self.bounds.extend(bounds);
+        self.bounds.extend(bounds.into_iter().map(Into::into));
         self
     }
 
     pub(crate) fn fmt_bounds(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         if !self.bounds.is_empty() {
-            write!(formatter, "\nwhere\n")?;
+            writeln!(formatter, "\nwhere")?;
 
             for bound in self.bounds.iter() {
-                bound.fmt_bound(formatter, BoundEndsWith::Comma)?;
+                bound.fmt(formatter)?;
+                writeln!(formatter, ",")?;
             }
         }
 
@@ -229,7 +403,7 @@ macro_rules! impl_bound_methods {
         /// Adds a trait bound that must be satified by the type.
         pub fn push_bound<T>(&mut self, bound: T) -> &mut Self
         where
-            T: Into<Type>
+            T: Into<$crate::type_def::TypeBound>
         {
             self.$($inner)+.push_bound(bound);
             self
@@ -239,7 +413,7 @@ macro_rules! impl_bound_methods {
         pub fn extend_bounds<I, T>(&mut self, bounds: I) -> &mut Self
         where
             I: IntoIterator<Item = T>,
-            T: Into<Type>,
+            T: Into<$crate::type_def::TypeBound>,
         {
             self.$($inner)+.extend_bounds(bounds);
             self
@@ -265,16 +439,21 @@ macro_rules! impl_bounds_methods {
             self
         }
 
-        /// Pushes a new bound for a given type.
-        pub fn push_bound(&mut self, bound: $crate::bounds::Bound) -> &mut Self {
+        /// Pushes a new `where` clause predicate, like a
+        /// [`Bound`](crate::Bound) or `"T: ?Sized"`.
+        pub fn push_bound<P>(&mut self, bound: P) -> &mut Self
+        where
+            P: Into<$crate::bounds::WherePredicate>
+        {
             self.$($inner)+.push_bound(bound);
             self
         }
 
         /// Extends the inner container with multiple bounds.
-        pub fn extend_bounds<I>(&mut self, bounds: I) -> &mut Self
+        pub fn extend_bounds<I, P>(&mut self, bounds: I) -> &mut Self
         where
-            I: IntoIterator<Item = $crate::bounds::Bound>
+            I: IntoIterator<Item = P>,
+            P: Into<$crate::bounds::WherePredicate>
         {
             self.$($inner)+.extend_bounds(bounds);
             self
@@ -284,3 +463,53 @@ macro_rules! impl_bounds_methods {
 
 pub(crate) use impl_bound_methods;
 pub(crate) use impl_bounds_methods;
+
+impl Visit for Bound {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        for bound in &self.bounds {
+            bound.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        for bound in &mut self.bounds {
+            bound.visit_types_mut(f);
+        }
+    }
+}
+
+impl Visit for WherePredicate {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        if let WherePredicate::Type { ty, bounds, .. } = self {
+            f(ty);
+
+            for bound in bounds {
+                bound.visit_types(f);
+            }
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        if let WherePredicate::Type { ty, bounds, .. } = self {
+            f(ty);
+
+            for bound in bounds {
+                bound.visit_types_mut(f);
+            }
+        }
+    }
+}
+
+impl Visit for Bounds {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        for bound in &self.bounds {
+            bound.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        for bound in &mut self.bounds {
+            bound.visit_types_mut(f);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt::{self, Write};
+
+use crate::attributes::Attributes;
+use crate::docs::Docs;
+use crate::error::CodegenError;
+use crate::expr::Expr;
+use crate::formatter::Formatter;
+use crate::validate::{Diagnostic, check_name};
+use crate::visit::{Text, Visit};
+use crate::type_def::Type;
+use crate::vis::Vis;
+
+use crate::impl_macros::{
+    impl_attr_methods,
+    impl_doc_methods,
+    impl_ty_methods,
+    impl_vis_methods,
+};
+
+
+/// Defines a `const` item, either on its own or associated with a trait or
+/// impl block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Const {
+    /// Name of the constant
+    name: String,
+    /// Constant documentation
+    docs: Docs,
+    /// Constant attributes
+    attrs: Attributes,
+    /// Constant visibility
+    vis: Vis,
+    /// Constant type
+    ty: Type,
+    /// Initializer expression. Only optional for trait constants.
+    value: Option<Expr>,
+}
+
+impl Const {
+    /// Return a new constant definition with the given name and type.
+    pub fn new<S, T>(name: S, ty: T) -> Self
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        Const {
+            name: name.as_ref().to_owned(),
+            docs: Docs::default(),
+            attrs: Attributes::default(),
+            vis: Vis::default(),
+            ty: ty.into(),
+            value: None,
+        }
+    }
+
+    /// Returns the name of the constant.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Set the initializer expression of the constant.
+    ///
+    /// Constants in a trait may leave this unset, otherwise it is required.
+    pub fn value<T>(&mut self, value: T) -> &mut Self
+    where
+        T: Into<Expr>,
+    {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Returns the initializer expression, if one is set.
+    pub fn get_value(&self) -> Option<&Expr> {
+        self.value.as_ref()
+    }
+
+    /// Reports problems with the constant's name.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        check_name(&self.name, path, diags);
+    }
+
+    /// Checks that the constant can be formatted, either within a trait or
+    /// elsewhere.
+    pub(crate) fn check(&self, is_trait: bool) -> Result<(), CodegenError> {
+        if is_trait && self.vis != Vis::Private {
+            return Err(CodegenError::TraitItemVis { path: self.name.clone() });
+        }
+
+        if !is_trait && self.value.is_none() {
+            return Err(CodegenError::MissingValue { path: self.name.clone() });
+        }
+
+        Ok(())
+    }
+
+    /// Formats the constant using the given formatter.
+    ///
+    /// Returns an error if a trait constant has a visibility modifier, or a
+    /// constant outside of a trait has no value. [`Scope::try_to_string`]
+    /// reports which.
+    ///
+    /// [`Scope::try_to_string`]: crate::Scope::try_to_string
+    pub fn fmt(&self, is_trait: bool, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.check(is_trait).map_err(|_| fmt::Error)?;
+
+        self.docs.fmt_docs(fmt)?;
+        self.attrs.fmt_attrs(fmt)?;
+
+        self.vis.fmt(fmt)?;
+
+        write!(fmt, "const {}: ", self.name)?;
+        self.ty.fmt(fmt)?;
+
+        match self.value {
+            Some(ref value) => {
+                write!(fmt, " = ")?;
+                value.fmt(fmt)?;
+                writeln!(fmt, ";")
+            }
+            None => writeln!(fmt, ";"),
+        }
+    }
+
+    impl_attr_methods!(attrs);
+    impl_doc_methods!(docs);
+    impl_ty_methods!(field => ty);
+    impl_vis_methods!(field => vis);
+}
+
+impl Visit for Const {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        f(&self.ty);
+
+        if let Some(value) = &self.value {
+            value.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        f(&mut self.ty);
+
+        if let Some(value) = &mut self.value {
+            value.visit_types_mut(f);
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+
+        if let Some(value) = &self.value {
+            value.visit_text(f);
+        }
+    }
+}
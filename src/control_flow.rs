@@ -0,0 +1,512 @@
+use std::fmt::{self, Write};
+
+use crate::block::Block;
+use crate::expr::Expr;
+use crate::formatter::Formatter;
+use crate::item::Item;
+use crate::pat::Pat;
+use crate::type_def::Type;
+use crate::visit::Visit;
+
+
+/// The condition of an `if` or `while`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cond {
+    /// A boolean expression.
+    Expr(Expr),
+    /// A `let pat = expr` pattern match.
+    Let(Pat, Expr),
+}
+
+/// Defines an `if`/`else if`/`else` chain.
+///
+/// ```
+/// use codegen::{Expr, If};
+///
+/// let mut chain = If::new("x > 0");
+/// chain.body().line("positive()");
+/// chain.new_else_if_let("Some(y)", "z").line("other(y)");
+/// chain.new_else().line("zero()");
+///
+/// let expr = Expr::from(chain);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct If {
+    branches: Vec<(Cond, Block)>,
+    else_block: Option<Block>,
+}
+
+/// Defines a `match` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    expr: Box<Expr>,
+    arms: Vec<Arm>,
+}
+
+/// Defines an arm of a `match` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arm {
+    pat: Pat,
+    guard: Option<Expr>,
+    body: Expr,
+}
+
+/// Defines a `loop`, `while`, `while let` or `for` loop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loop {
+    label: Option<String>,
+    kind: LoopKind,
+    body: Block,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LoopKind {
+    Loop,
+    While(Box<Cond>),
+    For(Pat, Box<Expr>),
+}
+
+impl Cond {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Cond::Expr(expr) => expr.fmt_cond(fmt),
+            Cond::Let(pat, expr) => {
+                write!(fmt, "let ")?;
+                pat.fmt(fmt)?;
+                write!(fmt, " = ")?;
+                expr.fmt_cond(fmt)
+            }
+        }
+    }
+
+    fn has_verbatim(&self) -> bool {
+        match self {
+            Cond::Expr(expr) | Cond::Let(_, expr) => expr.has_verbatim(),
+        }
+    }
+}
+
+impl If {
+    /// Returns an `if` with the given condition and an empty body.
+    pub fn new<E>(cond: E) -> Self
+    where
+        E: Into<Expr>,
+    {
+        If {
+            branches: vec![(Cond::Expr(cond.into()), Block::new(""))],
+            else_block: None,
+        }
+    }
+
+    /// Returns an `if let` with the given pattern and an empty body.
+    pub fn new_let<P, E>(pat: P, expr: E) -> Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        If {
+            branches: vec![(Cond::Let(pat.into(), expr.into()), Block::new(""))],
+            else_block: None,
+        }
+    }
+
+    /// Returns the body of the first branch.
+    pub fn body(&mut self) -> &mut Block {
+        &mut self.branches[0].1
+    }
+
+    /// Push an `else if` branch and return its body.
+    pub fn new_else_if<E>(&mut self, cond: E) -> &mut Block
+    where
+        E: Into<Expr>,
+    {
+        self.branches.push((Cond::Expr(cond.into()), Block::new("")));
+        &mut self.branches.last_mut().unwrap().1
+    }
+
+    /// Push an `else if let` branch and return its body.
+    pub fn new_else_if_let<P, E>(&mut self, pat: P, expr: E) -> &mut Block
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        self.branches.push((Cond::Let(pat.into(), expr.into()), Block::new("")));
+        &mut self.branches.last_mut().unwrap().1
+    }
+
+    /// Set an empty `else` branch and return its body.
+    pub fn new_else(&mut self) -> &mut Block {
+        self.else_block.insert(Block::new(""))
+    }
+
+    /// Formats the `if` chain using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        for (i, (cond, body)) in self.branches.iter().enumerate() {
+            if i != 0 {
+                write!(fmt, " else ")?;
+            }
+
+            write!(fmt, "if ")?;
+            cond.fmt(fmt)?;
+            write!(fmt, " ")?;
+            body.fmt_braces(fmt)?;
+        }
+
+        if let Some(else_block) = &self.else_block {
+            write!(fmt, " else ")?;
+            else_block.fmt_braces(fmt)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the chain holds any source text that is written as is.
+    pub(crate) fn has_verbatim(&self) -> bool {
+        self.branches.iter().any(|(cond, body)| cond.has_verbatim() || body.has_verbatim())
+            || self.else_block.as_ref().is_some_and(Block::has_verbatim)
+    }
+}
+
+impl Match {
+    /// Returns a `match` on the given expression, without arms.
+    pub fn new<E>(expr: E) -> Self
+    where
+        E: Into<Expr>,
+    {
+        Match {
+            expr: Box::new(expr.into()),
+            arms: vec![],
+        }
+    }
+
+    /// Push a new arm with an empty block body, returning a mutable
+    /// reference to it.
+    pub fn new_arm<P>(&mut self, pat: P) -> &mut Arm
+    where
+        P: Into<Pat>,
+    {
+        self.push_arm(Arm::new(pat));
+        self.arms.last_mut().unwrap()
+    }
+
+    /// Push an arm.
+    pub fn push_arm(&mut self, arm: Arm) -> &mut Self {
+        self.arms.push(arm);
+        self
+    }
+
+    /// Push an arm with the given pattern and body expression.
+    pub fn arm<P, E>(&mut self, pat: P, body: E) -> &mut Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        self.new_arm(pat).body(body);
+        self
+    }
+
+    /// Formats the `match` using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(fmt, "match ")?;
+        self.expr.fmt_cond(fmt)?;
+        writeln!(fmt, " {{")?;
+
+        fmt.indent(|fmt| {
+            for arm in &self.arms {
+                arm.fmt(fmt)?;
+            }
+
+            Ok(())
+        })?;
+
+        write!(fmt, "}}")
+    }
+
+    /// Whether the `match` holds any source text that is written as is.
+    pub(crate) fn has_verbatim(&self) -> bool {
+        self.expr.has_verbatim()
+            || self.arms.iter().any(|arm| {
+                arm.guard.as_ref().is_some_and(Expr::has_verbatim) || arm.body.has_verbatim()
+            })
+    }
+}
+
+impl Arm {
+    /// Returns an arm with the given pattern and an empty block body.
+    pub fn new<P>(pat: P) -> Self
+    where
+        P: Into<Pat>,
+    {
+        Arm {
+            pat: pat.into(),
+            guard: None,
+            body: Expr::Block(Block::new("")),
+        }
+    }
+
+    /// Set the `if` guard of the arm.
+    pub fn guard<E>(&mut self, guard: E) -> &mut Self
+    where
+        E: Into<Expr>,
+    {
+        self.guard = Some(guard.into());
+        self
+    }
+
+    /// Set the body of the arm to an expression.
+    pub fn body<E>(&mut self, body: E) -> &mut Self
+    where
+        E: Into<Expr>,
+    {
+        self.body = body.into();
+        self
+    }
+
+    /// Returns the block body of the arm, replacing an expression body with
+    /// an empty block.
+    pub fn block(&mut self) -> &mut Block {
+        if !matches!(self.body, Expr::Block(_)) {
+            self.body = Expr::Block(Block::new(""));
+        }
+
+        match self.body {
+            Expr::Block(ref mut block) => block,
+            _ => unreachable!(),
+        }
+    }
+
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.pat.fmt(fmt)?;
+
+        if let Some(guard) = &self.guard {
+            write!(fmt, " if ")?;
+            guard.fmt(fmt)?;
+        }
+
+        write!(fmt, " => ")?;
+        self.body.fmt(fmt)?;
+
+        if self.body.is_block_like() {
+            writeln!(fmt)
+        } else {
+            writeln!(fmt, ",")
+        }
+    }
+}
+
+impl Loop {
+    /// Returns a `loop` with an empty body.
+    pub fn new() -> Self {
+        Self::with_kind(LoopKind::Loop)
+    }
+
+    /// Returns a `while` loop with the given condition and an empty body.
+    pub fn new_while<E>(cond: E) -> Self
+    where
+        E: Into<Expr>,
+    {
+        Self::with_kind(LoopKind::While(Box::new(Cond::Expr(cond.into()))))
+    }
+
+    /// Returns a `while let` loop with the given pattern and an empty body.
+    pub fn new_while_let<P, E>(pat: P, expr: E) -> Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        let cond = Cond::Let(pat.into(), expr.into());
+        Self::with_kind(LoopKind::While(Box::new(cond)))
+    }
+
+    /// Returns a `for pat in iter` loop with an empty body.
+    pub fn new_for<P, E>(pat: P, iter: E) -> Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        Self::with_kind(LoopKind::For(pat.into(), Box::new(iter.into())))
+    }
+
+    fn with_kind(kind: LoopKind) -> Self {
+        Loop {
+            label: None,
+            kind,
+            body: Block::new(""),
+        }
+    }
+
+    /// Set the label of the loop, like `'outer`.
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Returns the body of the loop.
+    pub fn body(&mut self) -> &mut Block {
+        &mut self.body
+    }
+
+    /// Formats the loop using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(label) = &self.label {
+            write!(fmt, "{}: ", label)?;
+        }
+
+        match &self.kind {
+            LoopKind::Loop => write!(fmt, "loop ")?,
+            LoopKind::While(cond) => {
+                write!(fmt, "while ")?;
+                cond.fmt(fmt)?;
+                write!(fmt, " ")?;
+            }
+            LoopKind::For(pat, iter) => {
+                write!(fmt, "for ")?;
+                pat.fmt(fmt)?;
+                write!(fmt, " in ")?;
+                iter.fmt_cond(fmt)?;
+                write!(fmt, " ")?;
+            }
+        }
+
+        self.body.fmt_braces(fmt)
+    }
+
+    /// Whether the loop holds any source text that is written as is.
+    pub(crate) fn has_verbatim(&self) -> bool {
+        let head = match &self.kind {
+            LoopKind::Loop => false,
+            LoopKind::While(cond) => cond.has_verbatim(),
+            LoopKind::For(_, iter) => iter.has_verbatim(),
+        };
+
+        head || self.body.has_verbatim()
+    }
+}
+
+impl Default for Loop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Visit for Cond {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        match self {
+            Cond::Expr(expr) | Cond::Let(_, expr) => expr.visit_types(f),
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        match self {
+            Cond::Expr(expr) | Cond::Let(_, expr) => expr.visit_types_mut(f),
+        }
+    }
+
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        match self {
+            Cond::Expr(expr) | Cond::Let(_, expr) => expr.visit_items(f),
+        }
+    }
+}
+
+impl Visit for If {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        for (cond, body) in &self.branches {
+            cond.visit_types(f);
+            body.visit_types(f);
+        }
+
+        if let Some(else_block) = &self.else_block {
+            else_block.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        for (cond, body) in &mut self.branches {
+            cond.visit_types_mut(f);
+            body.visit_types_mut(f);
+        }
+
+        if let Some(else_block) = &mut self.else_block {
+            else_block.visit_types_mut(f);
+        }
+    }
+
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        for (cond, body) in &self.branches {
+            cond.visit_items(f);
+            body.visit_items(f);
+        }
+
+        if let Some(else_block) = &self.else_block {
+            else_block.visit_items(f);
+        }
+    }
+}
+
+impl Visit for Match {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.expr.visit_types(f);
+
+        for arm in &self.arms {
+            if let Some(guard) = &arm.guard {
+                guard.visit_types(f);
+            }
+            arm.body.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.expr.visit_types_mut(f);
+
+        for arm in &mut self.arms {
+            if let Some(guard) = &mut arm.guard {
+                guard.visit_types_mut(f);
+            }
+            arm.body.visit_types_mut(f);
+        }
+    }
+
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        self.expr.visit_items(f);
+
+        for arm in &self.arms {
+            if let Some(guard) = &arm.guard {
+                guard.visit_items(f);
+            }
+            arm.body.visit_items(f);
+        }
+    }
+}
+
+impl Visit for Loop {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        match &self.kind {
+            LoopKind::Loop => {}
+            LoopKind::While(cond) => cond.visit_types(f),
+            LoopKind::For(_, iter) => iter.visit_types(f),
+        }
+
+        self.body.visit_types(f);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        match &mut self.kind {
+            LoopKind::Loop => {}
+            LoopKind::While(cond) => cond.visit_types_mut(f),
+            LoopKind::For(_, iter) => iter.visit_types_mut(f),
+        }
+
+        self.body.visit_types_mut(f);
+    }
+
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        match &self.kind {
+            LoopKind::Loop => {}
+            LoopKind::While(cond) => cond.visit_items(f),
+            LoopKind::For(_, iter) => iter.visit_items(f),
+        }
+
+        self.body.visit_items(f);
+    }
+}
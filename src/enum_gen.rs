@@ -1,13 +1,17 @@
 use std::fmt;
 use std::ops::{Deref, DerefMut};
 
+use crate::error::CodegenError;
 use crate::formatter::Formatter;
-use crate::type_def::{TypeDef, impl_type_def_passthrough};
+use crate::ident::Edition;
+use crate::validate::{Diagnostic, Severity, check_duplicates, member_path};
+use crate::visit::{Text, Visit};
+use crate::type_def::{Type, TypeDef, impl_type_def_passthrough};
 use crate::variant::Variant;
 
 
 /// Defines an enumeration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Enum {
     type_def: TypeDef,
     variants: Vec<Variant>,
@@ -34,6 +38,60 @@ impl Enum {
         self
     }
 
+    /// Maps the names of the enum's variants, and their fields, to valid
+    /// identifiers, escaping keywords of the given edition. See
+    /// [`Ident::sanitize`].
+    ///
+    /// [`Ident::sanitize`]: crate::Ident::sanitize
+    pub fn sanitize_names(&mut self, edition: Edition) -> &mut Self {
+        for variant in &mut self.variants {
+            variant.sanitize_name(edition);
+        }
+
+        self
+    }
+
+    /// Returns the names of the enum's variants.
+    pub(crate) fn variant_names(&self) -> impl Iterator<Item = &str> {
+        self.variants.iter().map(Variant::name)
+    }
+
+    /// Reports problems with the enum and its variants.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        self.type_def.validate(path, diags);
+
+        for variant in &self.variants {
+            variant.validate(&member_path(path, variant.name()), diags);
+        }
+
+        check_duplicates(
+            self.variants.iter().map(Variant::name),
+            "variant",
+            |name| member_path(path, name),
+            diags,
+        );
+
+        let has_discriminant = self.variants.iter().any(|v| v.get_discriminant().is_some());
+        let all_unit = self.variants.iter().all(Variant::is_unit);
+
+        if has_discriminant && !all_unit && !self.type_def.has_repr() {
+            diags.push(Diagnostic::new(
+                Severity::Error,
+                path,
+                "discriminants on enums with non-unit variants require a `repr`",
+            ));
+        }
+    }
+
+    /// Checks that the enum can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        for variant in &self.variants {
+            variant.check().map_err(|err| err.in_item(self.type_def.name()))?;
+        }
+
+        Ok(())
+    }
+
     /// Formats the enum using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.type_def.fmt_head("enum", &[], fmt)?;
@@ -63,3 +121,29 @@ impl DerefMut for Enum {
         &mut self.type_def
     }
 }
+
+impl Visit for Enum {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.type_def.visit_types(f);
+
+        for variant in &self.variants {
+            variant.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.type_def.visit_types_mut(f);
+
+        for variant in &mut self.variants {
+            variant.visit_types_mut(f);
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.type_def.visit_text(f);
+
+        for variant in &self.variants {
+            variant.visit_text(f);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::parse::TypeParseError;
+
+
+/// An error caused by misusing a builder, or by an item that can't be
+/// formatted into valid code.
+///
+/// Every variant carries the path of the offending item, such as
+/// `a::b::Foo.field`, where modules are separated by `::` and the members of
+/// an item by `.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CodegenError {
+    /// Named fields were added to a tuple field list, or the other way around.
+    MixedFields {
+        /// Path to the item with the fields.
+        path: String,
+    },
+    /// A named or tuple field list has no fields.
+    EmptyFields {
+        /// Path to the item with the fields.
+        path: String,
+    },
+    /// A module was pushed into a scope that already defines one with the
+    /// same name.
+    DuplicateModule {
+        /// Path to the duplicate module.
+        path: String,
+    },
+    /// A fn outside of a trait has no body.
+    MissingFnBody {
+        /// Path to the fn.
+        path: String,
+    },
+    /// A fn in an `extern` block has a body.
+    ForeignFnBody {
+        /// Path to the fn.
+        path: String,
+    },
+    /// A trait fn or const has a visibility modifier.
+    TraitItemVis {
+        /// Path to the trait item.
+        path: String,
+    },
+    /// A const outside of a trait, or a static outside of an `extern` block,
+    /// has no value.
+    MissingValue {
+        /// Path to the const or static.
+        path: String,
+    },
+    /// A static in an `extern` block has a value.
+    ForeignStaticValue {
+        /// Path to the static.
+        path: String,
+    },
+    /// A type string could not be parsed.
+    InvalidType {
+        /// Path to the item using the type, or an empty string if the type
+        /// isn't part of an item yet.
+        path: String,
+        /// Why the type could not be parsed.
+        error: TypeParseError,
+    },
+}
+
+impl CodegenError {
+    /// Returns the path of the item that caused the error.
+    pub fn path(&self) -> &str {
+        match self {
+            CodegenError::MixedFields { path }
+            | CodegenError::EmptyFields { path }
+            | CodegenError::DuplicateModule { path }
+            | CodegenError::MissingFnBody { path }
+            | CodegenError::ForeignFnBody { path }
+            | CodegenError::TraitItemVis { path }
+            | CodegenError::MissingValue { path }
+            | CodegenError::ForeignStaticValue { path }
+            | CodegenError::InvalidType { path, .. } => path,
+        }
+    }
+
+    fn path_mut(&mut self) -> &mut String {
+        match self {
+            CodegenError::MixedFields { path }
+            | CodegenError::EmptyFields { path }
+            | CodegenError::DuplicateModule { path }
+            | CodegenError::MissingFnBody { path }
+            | CodegenError::ForeignFnBody { path }
+            | CodegenError::TraitItemVis { path }
+            | CodegenError::MissingValue { path }
+            | CodegenError::ForeignStaticValue { path }
+            | CodegenError::InvalidType { path, .. } => path,
+        }
+    }
+
+    /// Prefixes the path with the item it is a member of, e.g. `field`
+    /// becomes `Foo.field`.
+    pub(crate) fn in_item(mut self, name: &str) -> Self {
+        let path = self.path_mut();
+
+        *path = if path.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}.{}", name, path)
+        };
+        self
+    }
+
+    /// Prefixes the path with the module the item is defined in, e.g. `Foo`
+    /// becomes `a::Foo`.
+    pub(crate) fn in_module(mut self, name: &str) -> Self {
+        let path = self.path_mut();
+
+        *path = if path.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}::{}", name, path)
+        };
+        self
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            CodegenError::MixedFields { .. } => "named and tuple fields can not be mixed",
+            CodegenError::EmptyFields { .. } => "field lists can not be empty",
+            CodegenError::DuplicateModule { .. } => "a module with this name is already defined",
+            CodegenError::MissingFnBody { .. } => "fns outside of traits must have a body",
+            CodegenError::ForeignFnBody { .. } => "foreign fns can not have bodies",
+            CodegenError::TraitItemVis { .. } => "trait items do not have visibility modifiers",
+            CodegenError::MissingValue { .. } => "consts and statics must have a value",
+            CodegenError::ForeignStaticValue { .. } => "foreign statics can not have a value",
+            CodegenError::InvalidType { .. } => "types must be valid",
+        }
+    }
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path().is_empty() {
+            write!(f, "`{}`: ", self.path())?;
+        }
+
+        match self {
+            CodegenError::InvalidType { error, .. } => write!(f, "{}: {}", self.message(), error),
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+impl Error for CodegenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CodegenError::InvalidType { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
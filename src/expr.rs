@@ -0,0 +1,1143 @@
+use std::fmt::{self, Write};
+
+use crate::block::Block;
+use crate::control_flow::{If, Loop, Match};
+use crate::formatter::Formatter;
+use crate::item::Item;
+use crate::lit::Lit;
+use crate::type_def::Type;
+use crate::visit::{Text, Visit};
+
+
+/// An expression, such as a function call or an arithmetic operation.
+///
+/// Expressions are formatted with the parentheses needed to preserve their
+/// structure, so `Expr::path("a").binary(BinOp::Add, "b").binary(BinOp::Mul,
+/// "c")` renders as `(a + b) * c`.
+///
+/// ```
+/// use codegen::{BinOp, Expr};
+///
+/// let expr = Expr::path("items")
+///     .method("iter", None::<Expr>)
+///     .method("map", [Expr::closure(["x"], Expr::path("x").binary(BinOp::Mul, "2"))])
+///     .method("sum::<u32>", None::<Expr>);
+///
+/// assert_eq!(expr.to_string(), "items.iter().map(|x| x * 2).sum::<u32>()");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Source text that is written as is, and is treated as an operand that
+    /// never needs parentheses.
+    Verbatim(String),
+    /// A literal, like `1u8` or `"foo"`.
+    Lit(Lit),
+    /// A path, like `x` or `std::mem::swap`.
+    Path(String),
+    /// A call, like `f(a, b)`.
+    Call {
+        /// The function being called.
+        func: Box<Expr>,
+        /// The arguments.
+        args: Vec<Expr>,
+    },
+    /// A method call, like `x.f::<T>(a, b)`.
+    MethodCall {
+        /// The receiver.
+        receiver: Box<Expr>,
+        /// The method name.
+        method: String,
+        /// The turbofish generic arguments.
+        turbofish: Vec<Type>,
+        /// The arguments.
+        args: Vec<Expr>,
+    },
+    /// A field access, like `x.foo` or `x.0`.
+    Field {
+        /// The expression whose field is accessed.
+        base: Box<Expr>,
+        /// The field name or tuple index.
+        member: String,
+    },
+    /// An index, like `x[i]`.
+    Index {
+        /// The indexed expression.
+        base: Box<Expr>,
+        /// The index.
+        index: Box<Expr>,
+    },
+    /// A binary operation, like `a + b` or `a = b`.
+    Binary {
+        /// The operator.
+        op: BinOp,
+        /// The left hand side.
+        lhs: Box<Expr>,
+        /// The right hand side.
+        rhs: Box<Expr>,
+    },
+    /// A unary operation, like `!x`.
+    Unary {
+        /// The operator.
+        op: UnOp,
+        /// The operand.
+        expr: Box<Expr>,
+    },
+    /// A reference, like `&x` or `&mut x`.
+    Reference {
+        /// Whether or not the reference is mutable.
+        mutable: bool,
+        /// The referenced expression.
+        expr: Box<Expr>,
+    },
+    /// A cast, like `x as u8`.
+    Cast {
+        /// The expression being cast.
+        expr: Box<Expr>,
+        /// The type it is cast to.
+        ty: Type,
+    },
+    /// The `?` operator.
+    Try(Box<Expr>),
+    /// An `.await` expression.
+    Await(Box<Expr>),
+    /// A struct literal, like `Foo { a: 1, ..Default::default() }`.
+    Struct {
+        /// The path of the struct or variant.
+        path: String,
+        /// The fields and their values.
+        fields: Vec<(String, Expr)>,
+        /// The base expression after `..`, if any.
+        rest: Option<Box<Expr>>,
+    },
+    /// A tuple, like `(a, b)`.
+    Tuple(Vec<Expr>),
+    /// An array, like `[a, b]`.
+    Array(Vec<Expr>),
+    /// A range, like `a..b`, `a..=b` or `..`.
+    Range {
+        /// The start of the range.
+        start: Option<Box<Expr>>,
+        /// The end of the range.
+        end: Option<Box<Expr>>,
+        /// Whether or not the end is included.
+        inclusive: bool,
+    },
+    /// A closure, like `move |a, b: u8| a + b`.
+    Closure {
+        /// Whether or not the closure is `move`.
+        is_move: bool,
+        /// The argument names and their optional types.
+        args: Vec<(String, Option<Type>)>,
+        /// The closure body.
+        body: Box<Expr>,
+    },
+    /// A macro call, like `vec![a, b]`.
+    Macro {
+        /// The path of the macro, without the `!`.
+        path: String,
+        /// The delimiter around the arguments.
+        delimiter: Delimiter,
+        /// The arguments.
+        args: Vec<Expr>,
+    },
+    /// A block, like `{ .. }`, `unsafe { .. }` or `async move { .. }`.
+    Block(Block),
+    /// An `if`/`else if`/`else` chain.
+    If(If),
+    /// A `match` expression.
+    Match(Match),
+    /// A `loop`, `while` or `for` loop.
+    Loop(Loop),
+    /// A `break`, with an optional label and value.
+    Break {
+        /// The label of the loop to break out of, like `'outer`.
+        label: Option<String>,
+        /// The value to break with.
+        value: Option<Box<Expr>>,
+    },
+    /// A `continue`, with an optional label.
+    Continue(Option<String>),
+    /// A `return`, with an optional value.
+    Return(Option<Box<Expr>>),
+}
+
+/// A binary operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Rem,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// `^`
+    BitXor,
+    /// `&`
+    BitAnd,
+    /// `|`
+    BitOr,
+    /// `<<`
+    Shl,
+    /// `>>`
+    Shr,
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+    /// `=`
+    Assign,
+    /// `+=`
+    AddAssign,
+    /// `-=`
+    SubAssign,
+    /// `*=`
+    MulAssign,
+    /// `/=`
+    DivAssign,
+    /// `%=`
+    RemAssign,
+    /// `^=`
+    BitXorAssign,
+    /// `&=`
+    BitAndAssign,
+    /// `|=`
+    BitOrAssign,
+    /// `<<=`
+    ShlAssign,
+    /// `>>=`
+    ShrAssign,
+}
+
+/// A unary operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnOp {
+    /// `*`
+    Deref,
+    /// `!`
+    Not,
+    /// `-`
+    Neg,
+}
+
+/// The delimiter around the arguments of a macro call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Delimiter {
+    /// `foo!(..)`
+    #[default]
+    Paren,
+    /// `foo![..]`
+    Bracket,
+    /// `foo! {..}`
+    Brace,
+}
+
+/// Operator precedence, from loosest to tightest binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Prec {
+    Closure,
+    Assign,
+    Range,
+    Or,
+    And,
+    Compare,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Sum,
+    Product,
+    Cast,
+    Prefix,
+    Postfix,
+    Primary,
+}
+
+impl BinOp {
+    /// Returns the operator as written in source.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+            BinOp::BitXor => "^",
+            BinOp::BitAnd => "&",
+            BinOp::BitOr => "|",
+            BinOp::Shl => "<<",
+            BinOp::Shr => ">>",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::Assign => "=",
+            BinOp::AddAssign => "+=",
+            BinOp::SubAssign => "-=",
+            BinOp::MulAssign => "*=",
+            BinOp::DivAssign => "/=",
+            BinOp::RemAssign => "%=",
+            BinOp::BitXorAssign => "^=",
+            BinOp::BitAndAssign => "&=",
+            BinOp::BitOrAssign => "|=",
+            BinOp::ShlAssign => "<<=",
+            BinOp::ShrAssign => ">>=",
+        }
+    }
+
+    fn prec(&self) -> Prec {
+        match self {
+            BinOp::Mul | BinOp::Div | BinOp::Rem => Prec::Product,
+            BinOp::Add | BinOp::Sub => Prec::Sum,
+            BinOp::Shl | BinOp::Shr => Prec::Shift,
+            BinOp::BitAnd => Prec::BitAnd,
+            BinOp::BitXor => Prec::BitXor,
+            BinOp::BitOr => Prec::BitOr,
+            BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => {
+                Prec::Compare
+            }
+            BinOp::And => Prec::And,
+            BinOp::Or => Prec::Or,
+            _ => Prec::Assign,
+        }
+    }
+}
+
+impl UnOp {
+    /// Returns the operator as written in source.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnOp::Deref => "*",
+            UnOp::Not => "!",
+            UnOp::Neg => "-",
+        }
+    }
+}
+
+impl Expr {
+    /// Returns source text that is written as is.
+    pub fn verbatim<S>(src: S) -> Self
+    where
+        S: ToString
+    {
+        Expr::Verbatim(src.to_string())
+    }
+
+    /// Returns a literal, like `1` from `1` or `"foo"` from `"foo"`.
+    pub fn lit<T>(lit: T) -> Self
+    where
+        T: Into<Lit>
+    {
+        Expr::Lit(lit.into())
+    }
+
+    /// Returns a path expression.
+    pub fn path<S>(path: S) -> Self
+    where
+        S: ToString
+    {
+        Expr::Path(path.to_string())
+    }
+
+    /// Returns a tuple of the given expressions.
+    pub fn tuple<I, E>(items: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Expr>,
+    {
+        Expr::Tuple(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns an array of the given expressions.
+    pub fn array<I, E>(items: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Expr>,
+    {
+        Expr::Array(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns a struct literal with the given fields.
+    pub fn struct_lit<S, I, N, E>(path: S, fields: I) -> Self
+    where
+        S: ToString,
+        I: IntoIterator<Item = (N, E)>,
+        N: ToString,
+        E: Into<Expr>,
+    {
+        Expr::Struct {
+            path: path.to_string(),
+            fields: fields.into_iter()
+                .map(|(name, value)| (name.to_string(), value.into()))
+                .collect(),
+            rest: None,
+        }
+    }
+
+    /// Returns a unary operation.
+    pub fn unary<E>(op: UnOp, expr: E) -> Self
+    where
+        E: Into<Expr>
+    {
+        Expr::Unary { op, expr: Box::new(expr.into()) }
+    }
+
+    /// Returns a range between two optional bounds.
+    pub fn range<S, E>(start: Option<S>, end: Option<E>, inclusive: bool) -> Self
+    where
+        S: Into<Expr>,
+        E: Into<Expr>,
+    {
+        Expr::Range {
+            start: start.map(|start| Box::new(start.into())),
+            end: end.map(|end| Box::new(end.into())),
+            inclusive,
+        }
+    }
+
+    /// Returns a closure with untyped arguments.
+    pub fn closure<I, S, E>(args: I, body: E) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+        E: Into<Expr>,
+    {
+        Expr::Closure {
+            is_move: false,
+            args: args.into_iter().map(|arg| (arg.to_string(), None)).collect(),
+            body: Box::new(body.into()),
+        }
+    }
+
+    /// Returns a macro call with comma separated arguments.
+    pub fn macro_call<S, I, E>(path: S, delimiter: Delimiter, args: I) -> Self
+    where
+        S: ToString,
+        I: IntoIterator<Item = E>,
+        E: Into<Expr>,
+    {
+        Expr::Macro {
+            path: path.to_string(),
+            delimiter,
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns a call of this expression with the given arguments.
+    pub fn call<I, E>(self, args: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Expr>,
+    {
+        Expr::Call {
+            func: Box::new(self),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns a call of the method `method` on this expression.
+    ///
+    /// Turbofish arguments can be written as part of `method`, or set on
+    /// the returned `Expr::MethodCall`.
+    pub fn method<S, I, E>(self, method: S, args: I) -> Self
+    where
+        S: ToString,
+        I: IntoIterator<Item = E>,
+        E: Into<Expr>,
+    {
+        Expr::MethodCall {
+            receiver: Box::new(self),
+            method: method.to_string(),
+            turbofish: vec![],
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns an access of the field `member` of this expression.
+    pub fn field<S>(self, member: S) -> Self
+    where
+        S: ToString
+    {
+        Expr::Field { base: Box::new(self), member: member.to_string() }
+    }
+
+    /// Returns this expression indexed by `index`.
+    pub fn index<E>(self, index: E) -> Self
+    where
+        E: Into<Expr>
+    {
+        Expr::Index { base: Box::new(self), index: Box::new(index.into()) }
+    }
+
+    /// Returns a binary operation with this expression on the left.
+    pub fn binary<E>(self, op: BinOp, rhs: E) -> Self
+    where
+        E: Into<Expr>
+    {
+        Expr::Binary { op, lhs: Box::new(self), rhs: Box::new(rhs.into()) }
+    }
+
+    /// Returns a shared reference to this expression.
+    pub fn reference(self) -> Self {
+        Expr::Reference { mutable: false, expr: Box::new(self) }
+    }
+
+    /// Returns a mutable reference to this expression.
+    pub fn reference_mut(self) -> Self {
+        Expr::Reference { mutable: true, expr: Box::new(self) }
+    }
+
+    /// Returns this expression cast to `ty`.
+    pub fn cast<T>(self, ty: T) -> Self
+    where
+        T: Into<Type>
+    {
+        Expr::Cast { expr: Box::new(self), ty: ty.into() }
+    }
+
+    /// Returns this expression followed by the `?` operator.
+    pub fn propagate(self) -> Self {
+        Expr::Try(Box::new(self))
+    }
+
+    /// Returns this expression followed by `.await`.
+    pub fn awaited(self) -> Self {
+        Expr::Await(Box::new(self))
+    }
+
+    /// Returns a `return` of the given value.
+    pub fn ret<E>(value: E) -> Self
+    where
+        E: Into<Expr>
+    {
+        Expr::Return(Some(Box::new(value.into())))
+    }
+
+    /// Whether or not the expression ends with a block, like `if` and
+    /// `match` do.
+    pub(crate) fn is_block_like(&self) -> bool {
+        matches!(self, Expr::Block(_) | Expr::If(_) | Expr::Match(_) | Expr::Loop(_))
+    }
+
+    /// Whether or not a struct literal appears outside of any delimiters,
+    /// which isn't allowed in the condition of an `if`, `while` or `match`.
+    fn has_bare_struct(&self) -> bool {
+        match self {
+            Expr::Struct { .. } => true,
+            Expr::Binary { lhs, rhs, .. } => lhs.has_bare_struct() || rhs.has_bare_struct(),
+            Expr::Call { func: expr, .. }
+            | Expr::MethodCall { receiver: expr, .. }
+            | Expr::Field { base: expr, .. }
+            | Expr::Index { base: expr, .. }
+            | Expr::Unary { expr, .. }
+            | Expr::Reference { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Try(expr)
+            | Expr::Await(expr) => expr.has_bare_struct(),
+            Expr::Range { start, end, .. } => {
+                start.iter().chain(end).any(|bound| bound.has_bare_struct())
+            }
+            Expr::Break { value: Some(expr), .. } | Expr::Return(Some(expr)) => {
+                expr.has_bare_struct()
+            }
+            _ => false,
+        }
+    }
+
+    /// Formats the expression as the condition of an `if`, `while` or
+    /// `match`, wrapped in parentheses if it contains a struct literal.
+    pub(crate) fn fmt_cond(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if self.has_bare_struct() {
+            write!(fmt, "(")?;
+            self.fmt(fmt)?;
+            write!(fmt, ")")
+        } else {
+            self.fmt(fmt)
+        }
+    }
+
+    /// Formats the expression as the initializer of a `let .. else`, which
+    /// can't end with a block or be a lazy boolean operation.
+    pub(crate) fn fmt_let_else_init(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let needs_parens = self.is_block_like()
+            || matches!(self, Expr::Binary { op: BinOp::And | BinOp::Or, .. });
+
+        if needs_parens {
+            write!(fmt, "(")?;
+            self.fmt(fmt)?;
+            write!(fmt, ")")
+        } else {
+            self.fmt(fmt)
+        }
+    }
+
+    fn prec(&self) -> Prec {
+        match self {
+            Expr::Closure { .. }
+            | Expr::Break { .. }
+            | Expr::Continue(_)
+            | Expr::Return(_) => Prec::Closure,
+            Expr::Range { .. } => Prec::Range,
+            Expr::Binary { op, .. } => op.prec(),
+            Expr::Cast { .. } => Prec::Cast,
+            Expr::Unary { .. } | Expr::Reference { .. } => Prec::Prefix,
+            Expr::Lit(lit) if lit.is_negative() => Prec::Prefix,
+            Expr::Call { .. }
+            | Expr::MethodCall { .. }
+            | Expr::Field { .. }
+            | Expr::Index { .. }
+            | Expr::Try(_)
+            | Expr::Await(_) => Prec::Postfix,
+            _ => Prec::Primary,
+        }
+    }
+
+    /// Formats the expression, wrapped in parentheses if it binds looser
+    /// than `prec`, or as loose when `strict` is set.
+    fn fmt_operand(&self, prec: Prec, strict: bool, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if self.prec() < prec || (strict && self.prec() == prec) {
+            write!(fmt, "(")?;
+            self.fmt(fmt)?;
+            write!(fmt, ")")
+        } else {
+            self.fmt(fmt)
+        }
+    }
+
+    /// Formats the expression using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Verbatim(src) | Expr::Path(src) => write!(fmt, "{}", src),
+            Expr::Lit(lit) => lit.fmt(fmt),
+            Expr::Call { func, args } => {
+                // `(x.f)()` calls a field, `x.f()` would call a method
+                let strict = matches!(**func, Expr::Field { .. });
+                func.fmt_operand(Prec::Postfix, strict, fmt)?;
+                fmt_list("(", args, ")", fmt)
+            }
+            Expr::MethodCall { receiver, method, turbofish, args } => {
+                receiver.fmt_operand(Prec::Postfix, false, fmt)?;
+                write!(fmt, ".{}", method)?;
+
+                if !turbofish.is_empty() {
+                    write!(fmt, "::<")?;
+                    for (i, ty) in turbofish.iter().enumerate() {
+                        if i != 0 {
+                            write!(fmt, ", ")?;
+                        }
+                        ty.fmt(fmt)?;
+                    }
+                    write!(fmt, ">")?;
+                }
+
+                fmt_list("(", args, ")", fmt)
+            }
+            Expr::Field { base, member } => {
+                base.fmt_operand(Prec::Postfix, false, fmt)?;
+                write!(fmt, ".{}", member)
+            }
+            Expr::Index { base, index } => {
+                base.fmt_operand(Prec::Postfix, false, fmt)?;
+                write!(fmt, "[")?;
+                index.fmt(fmt)?;
+                write!(fmt, "]")
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                let prec = op.prec();
+
+                // `x as u8 < y` would parse `u8<` as the start of generics
+                let cast_lhs = matches!(op, BinOp::Lt | BinOp::Shl)
+                    && matches!(**lhs, Expr::Cast { .. });
+
+                let (lhs_strict, rhs_strict) = match prec {
+                    Prec::Assign => (true, false),
+                    Prec::Compare => (true, true),
+                    _ => (false, true),
+                };
+
+                if cast_lhs {
+                    write!(fmt, "(")?;
+                    lhs.fmt(fmt)?;
+                    write!(fmt, ")")?;
+                } else {
+                    lhs.fmt_operand(prec, lhs_strict, fmt)?;
+                }
+
+                write!(fmt, " {} ", op.as_str())?;
+                rhs.fmt_operand(prec, rhs_strict, fmt)
+            }
+            Expr::Unary { op, expr } => {
+                write!(fmt, "{}", op.as_str())?;
+                expr.fmt_operand(Prec::Prefix, false, fmt)
+            }
+            Expr::Reference { mutable, expr } => {
+                write!(fmt, "&")?;
+                if *mutable {
+                    write!(fmt, "mut ")?;
+                }
+                expr.fmt_operand(Prec::Prefix, false, fmt)
+            }
+            Expr::Cast { expr, ty } => {
+                expr.fmt_operand(Prec::Cast, false, fmt)?;
+                write!(fmt, " as ")?;
+                ty.fmt(fmt)
+            }
+            Expr::Try(expr) => {
+                expr.fmt_operand(Prec::Postfix, false, fmt)?;
+                write!(fmt, "?")
+            }
+            Expr::Await(expr) => {
+                expr.fmt_operand(Prec::Postfix, false, fmt)?;
+                write!(fmt, ".await")
+            }
+            Expr::Struct { path, fields, rest } => {
+                write!(fmt, "{} {{", path)?;
+
+                if fields.is_empty() && rest.is_none() {
+                    return write!(fmt, "}}");
+                }
+
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, ",")?;
+                    }
+
+                    // `Foo { x: x }` is written as `Foo { x }`
+                    if matches!(value, Expr::Path(path) if path == name) {
+                        write!(fmt, " {}", name)?;
+                    } else {
+                        write!(fmt, " {}: ", name)?;
+                        value.fmt(fmt)?;
+                    }
+                }
+
+                if let Some(rest) = rest {
+                    if !fields.is_empty() {
+                        write!(fmt, ",")?;
+                    }
+                    write!(fmt, " ..")?;
+                    rest.fmt(fmt)?;
+                }
+
+                write!(fmt, " }}")
+            }
+            Expr::Tuple(items) => {
+                write!(fmt, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, ", ")?;
+                    }
+                    item.fmt(fmt)?;
+                }
+
+                // `(x,)` is a tuple, `(x)` is not
+                if items.len() == 1 {
+                    write!(fmt, ",")?;
+                }
+
+                write!(fmt, ")")
+            }
+            Expr::Array(items) => fmt_list("[", items, "]", fmt),
+            Expr::Range { start, end, inclusive } => {
+                if let Some(start) = start {
+                    start.fmt_operand(Prec::Range, true, fmt)?;
+                }
+
+                write!(fmt, "{}", if *inclusive { "..=" } else { ".." })?;
+
+                if let Some(end) = end {
+                    end.fmt_operand(Prec::Range, true, fmt)?;
+                }
+
+                Ok(())
+            }
+            Expr::Closure { is_move, args, body } => {
+                if *is_move {
+                    write!(fmt, "move ")?;
+                }
+
+                write!(fmt, "|")?;
+                for (i, (name, ty)) in args.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, ", ")?;
+                    }
+
+                    write!(fmt, "{}", name)?;
+
+                    if let Some(ty) = ty {
+                        write!(fmt, ": ")?;
+                        ty.fmt(fmt)?;
+                    }
+                }
+                write!(fmt, "| ")?;
+
+                body.fmt(fmt)
+            }
+            Expr::Macro { path, delimiter, args } => {
+                write!(fmt, "{}!", path)?;
+
+                match delimiter {
+                    Delimiter::Paren => fmt_list("(", args, ")", fmt),
+                    Delimiter::Bracket => fmt_list("[", args, "]", fmt),
+                    Delimiter::Brace => fmt_list(" { ", args, " }", fmt),
+                }
+            }
+            Expr::Block(block) => block.fmt_expr(fmt),
+            Expr::If(v) => v.fmt(fmt),
+            Expr::Match(v) => v.fmt(fmt),
+            Expr::Loop(v) => v.fmt(fmt),
+            Expr::Break { label, value } => {
+                write!(fmt, "break")?;
+
+                if let Some(label) = label {
+                    write!(fmt, " {}", label)?;
+                }
+
+                if let Some(value) = value {
+                    write!(fmt, " ")?;
+                    value.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+            Expr::Continue(label) => {
+                write!(fmt, "continue")?;
+
+                if let Some(label) = label {
+                    write!(fmt, " {}", label)?;
+                }
+
+                Ok(())
+            }
+            Expr::Return(value) => {
+                write!(fmt, "return")?;
+
+                if let Some(value) = value {
+                    write!(fmt, " ")?;
+                    value.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether the expression holds any source text that is written as is.
+    pub(crate) fn has_verbatim(&self) -> bool {
+        match self {
+            Expr::Verbatim(_) => true,
+            Expr::Lit(_) | Expr::Path(_) | Expr::Continue(_) => false,
+            Expr::Block(v) => v.has_verbatim(),
+            Expr::If(v) => v.has_verbatim(),
+            Expr::Match(v) => v.has_verbatim(),
+            Expr::Loop(v) => v.has_verbatim(),
+            Expr::Break { value, .. } | Expr::Return(value) => {
+                value.as_deref().is_some_and(Expr::has_verbatim)
+            }
+            Expr::Call { func: receiver, args } | Expr::MethodCall { receiver, args, .. } => {
+                receiver.has_verbatim() || args.iter().any(Expr::has_verbatim)
+            }
+            Expr::Index { base: lhs, index: rhs } | Expr::Binary { lhs, rhs, .. } => {
+                lhs.has_verbatim() || rhs.has_verbatim()
+            }
+            Expr::Field { base: expr, .. }
+            | Expr::Unary { expr, .. }
+            | Expr::Reference { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Try(expr)
+            | Expr::Await(expr)
+            | Expr::Closure { body: expr, .. } => expr.has_verbatim(),
+            Expr::Struct { fields, rest, .. } => {
+                fields.iter().any(|(_, value)| value.has_verbatim())
+                    || rest.as_deref().is_some_and(Expr::has_verbatim)
+            }
+            Expr::Tuple(items) | Expr::Array(items) | Expr::Macro { args: items, .. } => {
+                items.iter().any(Expr::has_verbatim)
+            }
+            Expr::Range { start, end, .. } => {
+                start.iter().chain(end).any(|bound| bound.has_verbatim())
+            }
+        }
+    }
+}
+
+/// Formats a comma separated list of expressions between `open` and `close`.
+fn fmt_list(open: &str, items: &[Expr], close: &str, fmt: &mut Formatter<'_>) -> fmt::Result {
+    write!(fmt, "{}", open)?;
+
+    for (i, item) in items.iter().enumerate() {
+        if i != 0 {
+            write!(fmt, ", ")?;
+        }
+        item.fmt(fmt)?;
+    }
+
+    write!(fmt, "{}", close)
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        Expr::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
+
+impl<S> From<S> for Expr
+where
+    S: AsRef<str>
+{
+    fn from(src: S) -> Self {
+        Expr::Verbatim(src.as_ref().to_owned())
+    }
+}
+
+impl<'a> From<&'a Expr> for Expr {
+    fn from(src: &'a Expr) -> Self {
+        src.clone()
+    }
+}
+
+impl From<Lit> for Expr {
+    fn from(src: Lit) -> Self {
+        Expr::Lit(src)
+    }
+}
+
+impl From<Block> for Expr {
+    fn from(src: Block) -> Self {
+        Expr::Block(src)
+    }
+}
+
+impl From<If> for Expr {
+    fn from(src: If) -> Self {
+        Expr::If(src)
+    }
+}
+
+impl From<Match> for Expr {
+    fn from(src: Match) -> Self {
+        Expr::Match(src)
+    }
+}
+
+impl From<Loop> for Expr {
+    fn from(src: Loop) -> Self {
+        Expr::Loop(src)
+    }
+}
+
+impl Visit for Expr {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        match self {
+            Expr::Verbatim(_) | Expr::Lit(_) | Expr::Path(_) | Expr::Continue(_) => {}
+            Expr::Block(v) => v.visit_types(f),
+            Expr::If(v) => v.visit_types(f),
+            Expr::Match(v) => v.visit_types(f),
+            Expr::Loop(v) => v.visit_types(f),
+            Expr::Break { value, .. } | Expr::Return(value) => {
+                if let Some(value) = value {
+                    value.visit_types(f);
+                }
+            }
+            Expr::Call { func, args } => {
+                func.visit_types(f);
+                for arg in args {
+                    arg.visit_types(f);
+                }
+            }
+            Expr::MethodCall { receiver, turbofish, args, .. } => {
+                receiver.visit_types(f);
+                turbofish.iter().for_each(&mut *f);
+                for arg in args {
+                    arg.visit_types(f);
+                }
+            }
+            Expr::Field { base, .. } => base.visit_types(f),
+            Expr::Index { base, index } => {
+                base.visit_types(f);
+                index.visit_types(f);
+            }
+            Expr::Binary { lhs, rhs, .. } => {
+                lhs.visit_types(f);
+                rhs.visit_types(f);
+            }
+            Expr::Unary { expr, .. }
+            | Expr::Reference { expr, .. }
+            | Expr::Try(expr)
+            | Expr::Await(expr) => expr.visit_types(f),
+            Expr::Cast { expr, ty } => {
+                expr.visit_types(f);
+                f(ty);
+            }
+            Expr::Struct { fields, rest, .. } => {
+                for (_, value) in fields {
+                    value.visit_types(f);
+                }
+                if let Some(rest) = rest {
+                    rest.visit_types(f);
+                }
+            }
+            Expr::Tuple(items) | Expr::Array(items) | Expr::Macro { args: items, .. } => {
+                for item in items {
+                    item.visit_types(f);
+                }
+            }
+            Expr::Range { start, end, .. } => {
+                for bound in start.iter().chain(end) {
+                    bound.visit_types(f);
+                }
+            }
+            Expr::Closure { args, body, .. } => {
+                for ty in args.iter().filter_map(|(_, ty)| ty.as_ref()) {
+                    f(ty);
+                }
+                body.visit_types(f);
+            }
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        match self {
+            Expr::Verbatim(_) | Expr::Lit(_) | Expr::Path(_) | Expr::Continue(_) => {}
+            Expr::Block(v) => v.visit_types_mut(f),
+            Expr::If(v) => v.visit_types_mut(f),
+            Expr::Match(v) => v.visit_types_mut(f),
+            Expr::Loop(v) => v.visit_types_mut(f),
+            Expr::Break { value, .. } | Expr::Return(value) => {
+                if let Some(value) = value {
+                    value.visit_types_mut(f);
+                }
+            }
+            Expr::Call { func, args } => {
+                func.visit_types_mut(f);
+                for arg in args {
+                    arg.visit_types_mut(f);
+                }
+            }
+            Expr::MethodCall { receiver, turbofish, args, .. } => {
+                receiver.visit_types_mut(f);
+                turbofish.iter_mut().for_each(&mut *f);
+                for arg in args {
+                    arg.visit_types_mut(f);
+                }
+            }
+            Expr::Field { base, .. } => base.visit_types_mut(f),
+            Expr::Index { base, index } => {
+                base.visit_types_mut(f);
+                index.visit_types_mut(f);
+            }
+            Expr::Binary { lhs, rhs, .. } => {
+                lhs.visit_types_mut(f);
+                rhs.visit_types_mut(f);
+            }
+            Expr::Unary { expr, .. }
+            | Expr::Reference { expr, .. }
+            | Expr::Try(expr)
+            | Expr::Await(expr) => expr.visit_types_mut(f),
+            Expr::Cast { expr, ty } => {
+                expr.visit_types_mut(f);
+                f(ty);
+            }
+            Expr::Struct { fields, rest, .. } => {
+                for (_, value) in fields {
+                    value.visit_types_mut(f);
+                }
+                if let Some(rest) = rest {
+                    rest.visit_types_mut(f);
+                }
+            }
+            Expr::Tuple(items) | Expr::Array(items) | Expr::Macro { args: items, .. } => {
+                for item in items {
+                    item.visit_types_mut(f);
+                }
+            }
+            Expr::Range { start, end, .. } => {
+                for bound in start.iter_mut().chain(end) {
+                    bound.visit_types_mut(f);
+                }
+            }
+            Expr::Closure { args, body, .. } => {
+                for ty in args.iter_mut().filter_map(|(_, ty)| ty.as_mut()) {
+                    f(ty);
+                }
+                body.visit_types_mut(f);
+            }
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        let kind = if self.has_verbatim() { Text::Verbatim } else { Text::Body };
+        f(kind, &self.to_string());
+    }
+
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        match self {
+            Expr::Verbatim(_) | Expr::Lit(_) | Expr::Path(_) | Expr::Continue(_) => {}
+            Expr::Block(v) => v.visit_items(f),
+            Expr::If(v) => v.visit_items(f),
+            Expr::Match(v) => v.visit_items(f),
+            Expr::Loop(v) => v.visit_items(f),
+            Expr::Break { value, .. } | Expr::Return(value) => {
+                if let Some(value) = value {
+                    value.visit_items(f);
+                }
+            }
+            Expr::Call { func: receiver, args } | Expr::MethodCall { receiver, args, .. } => {
+                receiver.visit_items(f);
+                for arg in args {
+                    arg.visit_items(f);
+                }
+            }
+            Expr::Index { base: lhs, index: rhs } | Expr::Binary { lhs, rhs, .. } => {
+                lhs.visit_items(f);
+                rhs.visit_items(f);
+            }
+            Expr::Field { base: expr, .. }
+            | Expr::Unary { expr, .. }
+            | Expr::Reference { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Try(expr)
+            | Expr::Await(expr)
+            | Expr::Closure { body: expr, .. } => expr.visit_items(f),
+            Expr::Struct { fields, rest, .. } => {
+                for (_, value) in fields {
+                    value.visit_items(f);
+                }
+                if let Some(rest) = rest {
+                    rest.visit_items(f);
+                }
+            }
+            Expr::Tuple(items) | Expr::Array(items) | Expr::Macro { args: items, .. } => {
+                for item in items {
+                    item.visit_items(f);
+                }
+            }
+            Expr::Range { start, end, .. } => {
+                for bound in start.iter().chain(end) {
+                    bound.visit_items(f);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt::{self, Write};
+
+use crate::attributes::Attributes;
+use crate::docs::Docs;
+use crate::error::CodegenError;
+use crate::formatter::Formatter;
+use crate::validate::{Diagnostic, item_path};
+use crate::visit::{Text, Visit};
+use crate::function::Function;
+use crate::static_gen::Static;
+use crate::type_def::Type;
+
+use crate::impl_macros::{
+    impl_attr_methods,
+    impl_doc_methods,
+};
+
+
+/// Defines an `extern` block of foreign functions and statics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternBlock {
+    /// The ABI of the block, e.g. `"C"`
+    abi: Option<String>,
+    /// Whether or not this is an `unsafe extern` block
+    is_unsafe: bool,
+    /// Block documentation
+    docs: Docs,
+    /// Block attributes, e.g. `#[link(name = "foo")]`
+    attrs: Attributes,
+    /// Foreign items, in order
+    items: Vec<ForeignItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ForeignItem {
+    Fn(Function),
+    Static(Static),
+}
+
+impl ExternBlock {
+    /// Return a new, empty `extern` block with the given ABI.
+    pub fn new<S>(abi: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        ExternBlock {
+            abi: Some(abi.as_ref().to_owned()),
+            is_unsafe: false,
+            docs: Docs::default(),
+            attrs: Attributes::default(),
+            items: vec![],
+        }
+    }
+
+    /// Return a new, empty `extern` block without an explicit ABI.
+    pub fn new_default_abi() -> Self {
+        ExternBlock {
+            abi: None,
+            is_unsafe: false,
+            docs: Docs::default(),
+            attrs: Attributes::default(),
+            items: vec![],
+        }
+    }
+
+    /// Set whether this is an `unsafe extern` block, as required by the 2024
+    /// edition.
+    pub fn set_unsafe(&mut self, is_unsafe: bool) -> &mut Self {
+        self.is_unsafe = is_unsafe;
+        self
+    }
+
+    /// Add a `#[link(name = "...")]` attribute for the given library.
+    pub fn link<S>(&mut self, name: S) -> &mut Self
+    where
+        S: AsRef<str>
+    {
+        self.attrs.push_attr(format!("link(name = \"{}\")", name.as_ref()));
+        self
+    }
+
+    /// Push a new foreign function declaration, returning a mutable reference
+    /// to it.
+    pub fn new_fn<S>(&mut self, name: S) -> &mut Function
+    where
+        S: AsRef<str>
+    {
+        self.push_fn(Function::new_foreign_fn(name));
+
+        match self.items.last_mut().unwrap() {
+            ForeignItem::Fn(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a foreign function declaration.
+    pub fn push_fn(&mut self, item: Function) -> &mut Self {
+        self.items.push(ForeignItem::Fn(item));
+        self
+    }
+
+    /// Push a new foreign static declaration, returning a mutable reference to
+    /// it.
+    pub fn new_static<S, T>(&mut self, name: S, ty: T) -> &mut Static
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.push_static(Static::new(name, ty));
+
+        match self.items.last_mut().unwrap() {
+            ForeignItem::Static(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a foreign static declaration.
+    pub fn push_static(&mut self, item: Static) -> &mut Self {
+        self.items.push(ForeignItem::Static(item));
+        self
+    }
+
+    /// Reports problems with the items of the `extern` block, which are
+    /// defined in `module`.
+    pub(crate) fn validate(&self, module: &str, diags: &mut Vec<Diagnostic>) {
+        for item in &self.items {
+            match item {
+                ForeignItem::Fn(v) => v.validate(&item_path(module, v.name()), diags),
+                ForeignItem::Static(v) => v.validate(&item_path(module, v.name()), diags),
+            }
+        }
+    }
+
+    /// Checks that the `extern` block can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        for item in &self.items {
+            match item {
+                ForeignItem::Fn(v) => v.check_foreign()?,
+                ForeignItem::Static(v) => v.check_foreign()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Formats the `extern` block using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.docs.fmt_docs(fmt)?;
+        self.attrs.fmt_attrs(fmt)?;
+
+        if self.is_unsafe {
+            write!(fmt, "unsafe ")?;
+        }
+
+        write!(fmt, "extern")?;
+
+        if let Some(ref abi) = self.abi {
+            write!(fmt, " \"{}\"", abi)?;
+        }
+
+        fmt.block(|fmt| {
+            for item in &self.items {
+                match item {
+                    ForeignItem::Fn(v) => v.fmt_foreign(fmt)?,
+                    ForeignItem::Static(v) => v.fmt_foreign(fmt)?,
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    impl_attr_methods!(attrs);
+    impl_doc_methods!(docs);
+}
+
+impl Visit for ExternBlock {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        for item in &self.items {
+            match item {
+                ForeignItem::Fn(v) => v.visit_types(f),
+                ForeignItem::Static(v) => v.visit_types(f),
+            }
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        for item in &mut self.items {
+            match item {
+                ForeignItem::Fn(v) => v.visit_types_mut(f),
+                ForeignItem::Static(v) => v.visit_types_mut(f),
+            }
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+
+        for item in &self.items {
+            match item {
+                ForeignItem::Fn(v) => v.visit_text(f),
+                ForeignItem::Static(v) => v.visit_text(f),
+            }
+        }
+    }
+}
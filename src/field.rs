@@ -3,6 +3,8 @@ use std::fmt::{self, Write};
 use crate::attributes::Attributes;
 use crate::docs::Docs;
 use crate::formatter::Formatter;
+use crate::ident::{Edition, Ident};
+use crate::visit::{Text, Visit};
 use crate::vis::Vis;
 
 use crate::type_def::Type;
@@ -15,7 +17,7 @@ use crate::impl_macros::{
 };
 
 /// Defines a struct field.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Field {
     /// Field name
     name: Option<String>,
@@ -60,6 +62,21 @@ impl Field {
         }
     }
 
+    /// Returns the name of the field, if it is named.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Maps the field's name to a valid identifier, escaping keywords of the
+    /// given edition. See [`Ident::sanitize`].
+    pub fn sanitize_name(&mut self, edition: Edition) -> &mut Self {
+        if let Some(name) = self.name.as_mut() {
+            *name = Ident::sanitize_with_edition(&*name, edition).into();
+        }
+
+        self
+    }
+
     /// Whether or not this field is a named field
     pub fn is_named(&self) -> bool {
         self.name.is_some()
@@ -74,14 +91,14 @@ impl Field {
         }
 
         self.ty.fmt(formatter)?;
-        write!(formatter, ",\n")
+        writeln!(formatter, ",")
     }
 
     pub(crate) fn fmt_assoc_type_value(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         let name = self.name.as_ref().expect("associated type must be named");
         write!(formatter, "type {} = ", name)?;
         self.ty.fmt(formatter)?;
-        write!(formatter, ";\n")
+        writeln!(formatter, ";")
     }
 
     impl_attr_methods!(attrs);
@@ -89,3 +106,17 @@ impl Field {
     impl_ty_methods!(field => ty);
     impl_vis_methods!(field => vis);
 }
+
+impl Visit for Field {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        f(&self.ty);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        f(&mut self.ty);
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+    }
+}
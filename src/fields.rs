@@ -1,13 +1,16 @@
 use std::fmt::{self, Write};
 
-use crate::attributes::Attributes;
+use crate::error::CodegenError;
 use crate::field::Field;
 use crate::formatter::Formatter;
+use crate::ident::Edition;
+use crate::validate::{Diagnostic, check_duplicates, check_name, member_path};
+use crate::visit::{Text, Visit};
 use crate::type_def::Type;
 
 
 /// Defines a set of fields.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Fields {
     /// Represents an empty set of fields, i.e, a ZST struct.
     Empty,
@@ -18,33 +21,59 @@ pub enum Fields {
 }
 
 impl Fields {
+    /// Adds a named field, returning a mutable reference to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fields are already tuple fields.
     pub fn add_named<S, T>(&mut self, name: S, ty: T) -> &mut Field
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.try_add_named(name, ty).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Adds a named field, returning a mutable reference to it, or an error
+    /// if the fields are already tuple fields.
+    pub fn try_add_named<S, T>(&mut self, name: S, ty: T) -> Result<&mut Field, CodegenError>
     where
         S: AsRef<str>,
         T: Into<Type>,
     {
         // Create + add the field
-        self.named(name, ty);
+        self.try_named(name, ty)?;
 
         match self {
-            Self::Named(named_fields) => named_fields.last_mut().unwrap(),
+            Self::Named(named_fields) => Ok(named_fields.last_mut().unwrap()),
             _ => unreachable!("Fields::named did not catch an invalid Fields enum variant")
         }
     }
 
+    /// Adds a tuple field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fields are already named fields.
     pub fn add_tuple<T>(&mut self, r#type: T) -> &mut Self
     where
         T: Into<Type>
     {
-        match self {
-            Self::Tuple(tuple_fields) => tuple_fields.push(r#type.into()),
-            _ => panic!("cannot call 'add_tuple' on a collection of non-tuple fields.")
-        }
-
-        self
+        self.tuple(r#type)
     }
 
+    /// Pushes a named field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fields are already tuple fields.
     pub fn push_named(&mut self, field: Field) -> &mut Self {
+        self.try_push_named(field).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Pushes a named field, or returns an error if the fields are already
+    /// tuple fields.
+    pub fn try_push_named(&mut self, field: Field) -> Result<&mut Self, CodegenError> {
         match *self {
             Fields::Empty => {
                 *self = Fields::Named(vec![field]);
@@ -52,22 +81,53 @@ impl Fields {
             Fields::Named(ref mut fields) => {
                 fields.push(field);
             }
-            _ => panic!("field list is named"),
+            Fields::Tuple(_) => {
+                let path = field.name().unwrap_or_default().to_owned();
+                return Err(CodegenError::MixedFields { path });
+            }
         }
 
-        self
+        Ok(self)
     }
 
+    /// Adds a named field with the given name and type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fields are already tuple fields.
     pub fn named<S, T>(&mut self, name: S, ty: T) -> &mut Self
     where
         S: AsRef<str>,
         T: Into<Type>,
     {
-        self.push_named(Field::new_named(name, ty));
-        self
+        self.push_named(Field::new_named(name, ty))
     }
 
+    /// Adds a named field with the given name and type, or returns an error
+    /// if the fields are already tuple fields.
+    pub fn try_named<S, T>(&mut self, name: S, ty: T) -> Result<&mut Self, CodegenError>
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.try_push_named(Field::new_named(name, ty))
+    }
+
+    /// Adds a tuple field with the given type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fields are already named fields.
     pub fn tuple<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        self.try_tuple(ty).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Adds a tuple field with the given type, or returns an error if the
+    /// fields are already named fields.
+    pub fn try_tuple<T>(&mut self, ty: T) -> Result<&mut Self, CodegenError>
     where
         T: Into<Type>,
     {
@@ -78,17 +138,67 @@ impl Fields {
             Fields::Tuple(ref mut fields) => {
                 fields.push(ty.into());
             }
-            _ => panic!("field list is tuple"),
+            Fields::Named(_) => {
+                return Err(CodegenError::MixedFields { path: String::new() });
+            }
         }
 
-        self
+        Ok(self)
+    }
+
+    /// Returns the names of the named fields.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        let fields = match self {
+            Fields::Named(fields) => &fields[..],
+            _ => &[],
+        };
+
+        fields.iter().filter_map(Field::name)
+    }
+
+    /// Maps the names of named fields to valid identifiers.
+    pub(crate) fn sanitize_names(&mut self, edition: Edition) {
+        if let Fields::Named(fields) = self {
+            for field in fields {
+                field.sanitize_name(edition);
+            }
+        }
     }
 
+    /// Reports invalid and duplicate field names.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        if let Fields::Named(fields) = self {
+            let names = || fields.iter().filter_map(Field::name);
+
+            for name in names() {
+                check_name(name, &member_path(path, name), diags);
+            }
+
+            check_duplicates(names(), "field", |name| member_path(path, name), diags);
+        }
+    }
+
+    /// Checks that the fields can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        match self {
+            Fields::Named(fields) if fields.is_empty() => {
+                Err(CodegenError::EmptyFields { path: String::new() })
+            }
+            Fields::Tuple(tys) if tys.is_empty() => {
+                Err(CodegenError::EmptyFields { path: String::new() })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Formats the fields using the given formatter.
+    ///
+    /// Returns an error if a named or tuple field list is empty.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.check().map_err(|_| fmt::Error)?;
+
         match *self {
             Fields::Named(ref fields) => {
-                assert!(!fields.is_empty());
-
                 fmt.block(|fmt| {
                     for field in fields {
                         field.fmt_field(fmt)?;
@@ -98,8 +208,6 @@ impl Fields {
                 })?;
             }
             Fields::Tuple(ref tys) => {
-                assert!(!tys.is_empty());
-
                 write!(fmt, "(")?;
 
                 for (i, ty) in tys.iter().enumerate() {
@@ -117,3 +225,37 @@ impl Fields {
         Ok(())
     }
 }
+
+impl Visit for Fields {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        match self {
+            Fields::Empty => {}
+            Fields::Tuple(tys) => tys.iter().for_each(f),
+            Fields::Named(fields) => {
+                for field in fields {
+                    field.visit_types(f);
+                }
+            }
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        match self {
+            Fields::Empty => {}
+            Fields::Tuple(tys) => tys.iter_mut().for_each(f),
+            Fields::Named(fields) => {
+                for field in fields {
+                    field.visit_types_mut(f);
+                }
+            }
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        if let Fields::Named(fields) = self {
+            for field in fields {
+                field.visit_text(f);
+            }
+        }
+    }
+}
@@ -35,9 +35,9 @@ impl<'a> Formatter<'a> {
             write!(self, " ")?;
         }
 
-        write!(self, "{{\n")?;
+        writeln!(self, "{{")?;
         self.indent(f)?;
-        write!(self, "}}\n")?;
+        writeln!(self, "}}")?;
         Ok(())
     }
 
@@ -57,9 +57,19 @@ impl<'a> Formatter<'a> {
         self.dst.is_empty() || self.dst.as_bytes().last() == Some(&b'\n')
     }
 
+    /// Write `s` without indenting any of its lines but the first, for
+    /// source text like raw strings where the indentation would matter.
+    pub(crate) fn write_unindented(&mut self, s: &str) {
+        if self.is_start_of_line() && !s.is_empty() {
+            self.push_spaces();
+        }
+
+        self.dst.push_str(s);
+    }
+
     fn push_spaces(&mut self) {
         for _ in 0..self.spaces {
-            self.dst.push_str(" ");
+            self.dst.push(' ');
         }
     }
 }
@@ -71,7 +81,7 @@ impl<'a> fmt::Write for Formatter<'a> {
 
         for line in s.lines() {
             if !first {
-                self.dst.push_str("\n");
+                self.dst.push('\n');
             }
 
             first = false;
@@ -89,7 +99,7 @@ impl<'a> fmt::Write for Formatter<'a> {
         }
 
         if s.as_bytes().last() == Some(&b'\n') {
-            self.dst.push_str("\n");
+            self.dst.push('\n');
         }
 
         Ok(())
@@ -1,13 +1,18 @@
 use std::fmt::{self, Write};
 
+use crate::arg::{Arg, Receiver};
+use crate::signature::Signature;
 use crate::attributes::Attributes;
 use crate::block::Block;
-use crate::body::Body;
-use crate::bounds::Bounds;
+use crate::expr::Expr;
 use crate::docs::Docs;
-use crate::field::Field;
+use crate::error::CodegenError;
 use crate::formatter::Formatter;
-use crate::generics::Generics;
+use crate::pat::Pat;
+use crate::validate::{Diagnostic, check_name};
+use crate::visit::{Text, Visit};
+use crate::item::Item;
+use crate::stmt::Stmt;
 use crate::type_def::Type;
 use crate::vis::Vis;
 
@@ -21,34 +26,20 @@ use crate::impl_macros::{
 
 
 /// Defines a function.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Function {
-    /// Name of the function
-    name: String,
+    /// Function signature
+    sig: Signature,
     /// Function documentation
     docs: Docs,
     /// A lint attribute used to suppress a warning or error
     allow: Option<String>,
     /// Function visibility
     vis: Vis,
-    /// Function generics
-    generics: Generics,
-    /// If the function takes `&self` or `&mut self`
-    arg_self: Option<&'static str>,
-    /// Function arguments
-    args: Vec<Field>,
-    /// Return type
-    ret: Option<Type>,
-    /// Where bounds
-    bounds: Bounds,
     /// Body contents
-    body: Option<Vec<Body>>,
+    body: Option<Vec<Stmt>>,
     /// Function attributes, e.g., `#[no_mangle]`.
     attrs: Attributes,
-    /// Function `extern` ABI
-    extern_abi: Option<String>,
-    /// Whether or not this function is `async` or not
-    is_async: bool,
 }
 
 impl Function {
@@ -57,21 +48,16 @@ impl Function {
     where
         S: AsRef<str>
     {
-        Self {
-            name: name.as_ref().to_owned(),
-            docs: Docs::default(),
-            allow: None,
-            vis: Vis::default(),
-            generics: Generics::default(),
-            arg_self: None,
-            args: vec![],
-            ret: None,
-            bounds: Bounds::default(),
-            body: None,
-            attrs: Attributes::default(),
-            extern_abi: None,
-            is_async: false,
-        }
+        Self::with_body(Signature::new(name), None)
+    }
+
+    /// Creates a new foreign function declaration, for use in an `extern`
+    /// block.
+    pub fn new_foreign_fn<S>(name: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        Self::new_trait_fn(name)
     }
 
     /// Return a new function definition.
@@ -79,23 +65,42 @@ impl Function {
     where
         S: AsRef<str>
     {
+        Self::from_signature(Signature::new(name))
+    }
+
+    /// Return a new function definition with the given signature and an
+    /// empty body.
+    pub fn from_signature(sig: Signature) -> Self {
+        Self::with_body(sig, Some(vec![]))
+    }
+
+    /// Creates a new function definition for a trait, with the given
+    /// signature and no body.
+    pub fn trait_fn_from_signature(sig: Signature) -> Self {
+        Self::with_body(sig, None)
+    }
+
+    fn with_body(sig: Signature, body: Option<Vec<Stmt>>) -> Self {
         Function {
-            name: name.as_ref().to_owned(),
+            sig,
             docs: Docs::default(),
             allow: None,
             vis: Vis::default(),
-            generics: Generics::default(),
-            arg_self: None,
-            args: vec![],
-            ret: None,
-            bounds: Bounds::default(),
-            body: Some(vec![]),
+            body,
             attrs: Attributes::default(),
-            extern_abi: None,
-            is_async: false,
         }
     }
 
+    /// Returns the signature of the function.
+    pub fn signature(&self) -> &Signature {
+        &self.sig
+    }
+
+    /// Returns the signature of the function mutably.
+    pub fn signature_mut(&mut self) -> &mut Signature {
+        &mut self.sig
+    }
+
     /// Specify lint attribute to supress a warning or error.
     pub fn allow(&mut self, allow: &str) -> &mut Self {
         self.allow = Some(allow.to_string());
@@ -104,35 +109,77 @@ impl Function {
 
     /// Set whether this function is async or not
     pub fn set_async(&mut self, is_async: bool) -> &mut Self {
-        self.is_async = is_async;
+        self.sig.set_async(is_async);
+        self
+    }
+
+    /// Set whether this function is `const` or not.
+    pub fn set_const(&mut self, is_const: bool) -> &mut Self {
+        self.sig.set_const(is_const);
+        self
+    }
+
+    /// Set whether this function is `unsafe` or not.
+    pub fn set_unsafe(&mut self, is_unsafe: bool) -> &mut Self {
+        self.sig.set_unsafe(is_unsafe);
+        self
+    }
+
+    /// Set whether this function takes C-style variadic arguments (`...`).
+    /// Only foreign functions may be variadic.
+    pub fn set_variadic(&mut self, is_variadic: bool) -> &mut Self {
+        self.sig.set_variadic(is_variadic);
         self
     }
 
     /// Add `self` as a function argument.
     pub fn arg_self(&mut self) -> &mut Self {
-        self.arg_self = Some("self");
-        self
+        self.receiver(Receiver::Value { mutable: false })
     }
 
     /// Add `&self` as a function argument.
     pub fn arg_ref_self(&mut self) -> &mut Self {
-        self.arg_self = Some("&self");
-        self
+        self.receiver(Receiver::Ref { lifetime: None, mutable: false })
     }
 
     /// Add `&mut self` as a function argument.
     pub fn arg_mut_self(&mut self) -> &mut Self {
-        self.arg_self = Some("&mut self");
+        self.receiver(Receiver::Ref { lifetime: None, mutable: true })
+    }
+
+    /// Set the `self` argument, like `&'a self` or `self: Box<Self>`.
+    pub fn receiver(&mut self, receiver: Receiver) -> &mut Self {
+        self.sig.receiver(receiver);
         self
     }
 
-    /// Add a function argument.
-    pub fn arg<S, T>(&mut self, name: S, ty: T) -> &mut Self
+    /// Returns the `self` argument, if any.
+    pub fn get_receiver(&self) -> Option<&Receiver> {
+        self.sig.get_receiver()
+    }
+
+    /// Add a function argument, binding a name or pattern like `(a, b)`.
+    pub fn arg<P, T>(&mut self, pat: P, ty: T) -> &mut Self
+    where
+        P: Into<Pat>,
+        T: Into<Type>,
+    {
+        self.push_arg(Arg::new(pat, ty))
+    }
+
+    /// Push a new function argument, returning a mutable reference to it so
+    /// that attributes can be added.
+    pub fn new_arg<P, T>(&mut self, pat: P, ty: T) -> &mut Arg
     where
-        S: AsRef<str>,
+        P: Into<Pat>,
         T: Into<Type>,
     {
-        self.args.push(Field::new_named(name, ty));
+        self.sig.new_arg(pat, ty)
+    }
+
+    /// Push a function argument.
+    pub fn push_arg(&mut self, arg: Arg) -> &mut Self {
+        self.sig.push_arg(arg);
         self
     }
 
@@ -141,7 +188,7 @@ impl Function {
     where
         T: Into<Type>,
     {
-        self.ret = Some(ty.into());
+        self.sig.ret(ty);
         self
     }
 
@@ -152,11 +199,35 @@ impl Function {
     {
         self.body
             .get_or_insert(vec![])
-            .push(Body::String(line.to_string()));
+            .push(Stmt::Verbatim(line.to_string()));
+
+        self
+    }
 
+    /// Push an expression to the function implementation, as a line of its
+    /// own.
+    pub fn push_expr<E>(&mut self, expr: E) -> &mut Self
+    where
+        E: Into<Expr>,
+    {
+        self.body.get_or_insert(vec![]).push(Stmt::Expr(expr.into()));
         self
     }
 
+    /// Push a `let pat = init else { .. };` statement to the function
+    /// implementation, and return the `else` block.
+    pub fn new_let_else<P, E>(&mut self, pat: P, init: E) -> &mut Block
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        self.push_stmt(Stmt::let_else(pat, init, Block::new("")));
+
+        match *self.body.as_mut().unwrap().last_mut().unwrap() {
+            Stmt::LetElse { ref mut else_block, .. } => else_block,
+            _ => unreachable!(),
+        }
+    }
 
     /// Specify an `extern` ABI for the function.
     /// ```
@@ -168,66 +239,105 @@ impl Function {
     /// extern_func.extern_abi("C");
     /// ```
     pub fn extern_abi(&mut self, abi: &str) -> &mut Self {
-        self.extern_abi.replace(abi.to_string());
+        self.sig.extern_abi(abi);
         self
     }
 
     /// Push a block to the function implementation
     pub fn push_block(&mut self, block: Block) -> &mut Self {
-        self.body.get_or_insert(vec![]).push(Body::Block(block));
+        self.push_stmt(Stmt::Expr(Expr::Block(block)))
+    }
 
+    /// Push a statement to the function implementation.
+    pub fn push_stmt(&mut self, stmt: Stmt) -> &mut Self {
+        self.body.get_or_insert(vec![]).push(stmt);
         self
     }
 
-    /// Formats the function using the given formatter.
-    pub fn fmt(&self, is_trait: bool, fmt: &mut Formatter<'_>) -> fmt::Result {
-        self.docs.fmt_docs(fmt)?;
-
-        if let Some(ref allow) = self.allow {
-            write!(fmt, "#[allow({})]\n", allow)?;
-        }
+    /// Insert a statement into the function implementation at `index`,
+    /// shifting the statements after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of statements.
+    pub fn insert_stmt(&mut self, index: usize, stmt: Stmt) -> &mut Self {
+        self.body.get_or_insert(vec![]).insert(index, stmt);
+        self
+    }
 
-        self.attrs.fmt_attrs(fmt)?;
+    /// Push a nested item, like a helper `fn` or `struct`, to the function
+    /// implementation.
+    pub fn push_item<I>(&mut self, item: I) -> &mut Self
+    where
+        I: Into<Item>,
+    {
+        self.push_stmt(Stmt::Item(item.into()))
+    }
 
-        if is_trait {
-            assert!(self.vis == Vis::Private, "trait fns do not have visibility modifiers");
-        }
+    /// Returns the statements of the function implementation, if it has
+    /// one.
+    pub fn stmts(&self) -> Option<&[Stmt]> {
+        self.body.as_deref()
+    }
 
-        self.vis.fmt(fmt)?;
+    /// Returns the name of the function.
+    pub fn name(&self) -> &str {
+        self.sig.name()
+    }
 
-        if let Some(ref extern_abi) = self.extern_abi {
-            write!(fmt, "extern \"{extern_abi}\" ", extern_abi = extern_abi)?;
-        }
+    /// Reports problems with the function's name and generics, and with the
+    /// items nested in its body, like `path::Helper`.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        check_name(self.name(), path, diags);
+        self.sig.generics().validate(path, diags);
 
-        if self.is_async {
-            write!(fmt, "async ")?;
-        }
+        self.visit_items(&mut |item| item.validate(path, diags));
+    }
 
-        write!(fmt, "fn {}", self.name)?;
-        self.generics.fmt_generics(fmt)?;
+    /// Checks that the function, and every item nested in its body, can be
+    /// formatted, either within a trait or elsewhere.
+    pub(crate) fn check(&self, is_trait: bool) -> Result<(), CodegenError> {
+        let path = || self.name().to_owned();
 
-        write!(fmt, "(")?;
+        if is_trait && self.vis != Vis::Private {
+            return Err(CodegenError::TraitItemVis { path: path() });
+        }
 
-        if let Some(ref s) = self.arg_self {
-            write!(fmt, "{}", s)?;
+        if !is_trait && self.body.is_none() {
+            return Err(CodegenError::MissingFnBody { path: path() });
         }
 
-        for (i, arg) in self.args.iter().enumerate() {
-            if i != 0 || self.arg_self.is_some() {
-                write!(fmt, ", ")?;
+        let mut res = Ok(());
+
+        self.visit_items(&mut |item| {
+            if res.is_ok() {
+                res = item.check().map_err(|err| err.in_module(self.name()));
             }
+        });
+
+        res
+    }
 
-            arg.fmt_field(fmt)?;
+    /// Checks that the function can be formatted as a foreign function.
+    pub(crate) fn check_foreign(&self) -> Result<(), CodegenError> {
+        if self.body.as_ref().is_some_and(|body| !body.is_empty()) {
+            return Err(CodegenError::ForeignFnBody { path: self.name().to_owned() });
         }
 
-        write!(fmt, ")")?;
+        Ok(())
+    }
 
-        if let Some(ref ret) = self.ret {
-            write!(fmt, " -> ")?;
-            ret.fmt(fmt)?;
-        }
+    /// Formats the function using the given formatter.
+    ///
+    /// Returns an error if a trait function has a visibility modifier, or a
+    /// function outside of a trait has no body. [`Scope::try_to_string`]
+    /// reports which.
+    ///
+    /// [`Scope::try_to_string`]: crate::Scope::try_to_string
+    pub fn fmt(&self, is_trait: bool, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.check(is_trait).map_err(|_| fmt::Error)?;
 
-        self.bounds.fmt_bounds(fmt)?;
+        self.fmt_head(fmt)?;
 
         match self.body {
             Some(ref body) => fmt.block(|fmt| {
@@ -237,19 +347,68 @@ impl Function {
 
                 Ok(())
             }),
-            None => {
-                if !is_trait {
-                    panic!("impl blocks must define fn bodies");
-                }
+            None => writeln!(fmt, ";"),
+        }
+    }
 
-                write!(fmt, ";\n")
-            }
+    /// Formats the function as a foreign function declaration in an `extern`
+    /// block.
+    pub(crate) fn fmt_foreign(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.check_foreign().map_err(|_| fmt::Error)?;
+
+        self.fmt_head(fmt)?;
+        writeln!(fmt, ";")
+    }
+
+    /// Formats everything up to the function body.
+    fn fmt_head(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.docs.fmt_docs(fmt)?;
+
+        if let Some(ref allow) = self.allow {
+            writeln!(fmt, "#[allow({})]", allow)?;
         }
+
+        self.attrs.fmt_attrs(fmt)?;
+        self.vis.fmt(fmt)?;
+        self.sig.fmt(fmt)
     }
 
     impl_attr_methods!(attrs);
-    impl_bounds_methods!(bounds);
+    impl_bounds_methods!(sig);
     impl_doc_methods!(docs);
-    impl_generic_methods!(generics);
+    impl_generic_methods!(sig);
     impl_vis_methods!(field => vis);
 }
+
+impl Visit for Function {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.sig.visit_types(f);
+
+        for body in self.body.iter().flatten() {
+            body.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.sig.visit_types_mut(f);
+
+        for body in self.body.iter_mut().flatten() {
+            body.visit_types_mut(f);
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+        self.sig.visit_text(f);
+
+        for body in self.body.iter().flatten() {
+            body.visit_text(f);
+        }
+    }
+
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        for body in self.body.iter().flatten() {
+            body.visit_items(f);
+        }
+    }
+}
@@ -1,20 +1,264 @@
 use std::fmt::{self, Write};
 
 use crate::formatter::Formatter;
-use crate::type_def::Type;
+use crate::parse::Parser;
+use crate::validate::{Diagnostic, check_duplicates};
+use crate::visit::Visit;
+use crate::type_def::{Type, TypeBound};
 
 
+/// A generic parameter, like `'a: 'b`, `T: Clone = u8` or
+/// `const N: usize = 4`.
+///
+/// ```
+/// use codegen::GenericParam;
+///
+/// let param = GenericParam::type_param("T").bound("Clone").default("u8");
+/// assert_eq!(param.to_string(), "T: Clone = u8");
+///
+/// let param = GenericParam::from("const N: usize = 4");
+/// assert_eq!(param, GenericParam::const_param("N", "usize").default_value("4"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenericParam {
+    /// A lifetime parameter, like `'a: 'b`.
+    Lifetime {
+        /// The lifetime, including the leading `'`.
+        name: String,
+        /// The lifetimes that this one outlives.
+        outlives: Vec<String>,
+    },
+    /// A type parameter, like `T: Clone = u8`.
+    Type {
+        /// The name of the parameter.
+        name: String,
+        /// The inline bounds of the parameter.
+        bounds: Vec<TypeBound>,
+        /// The default type, if any.
+        default: Option<Type>,
+    },
+    /// A const parameter, like `const N: usize = 4`.
+    Const {
+        /// The name of the parameter.
+        name: String,
+        /// The type of the parameter.
+        ty: Type,
+        /// The default value, written out verbatim.
+        default: Option<String>,
+    },
+}
+
+/// The generic parameters of an item.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Generics {
-    lifetimes: Vec<String>,
-    generics: Vec<Type>,
+    params: Vec<GenericParam>,
+}
+
+impl GenericParam {
+    /// Returns a lifetime parameter, like `'a`.
+    pub fn lifetime<S>(name: S) -> Self
+    where
+        S: ToString,
+    {
+        GenericParam::Lifetime {
+            name: name.to_string(),
+            outlives: vec![],
+        }
+    }
+
+    /// Returns a type parameter without bounds or a default.
+    pub fn type_param<S>(name: S) -> Self
+    where
+        S: ToString,
+    {
+        GenericParam::Type {
+            name: name.to_string(),
+            bounds: vec![],
+            default: None,
+        }
+    }
+
+    /// Returns a const parameter of the given type, without a default.
+    pub fn const_param<S, T>(name: S, ty: T) -> Self
+    where
+        S: ToString,
+        T: Into<Type>,
+    {
+        GenericParam::Const {
+            name: name.to_string(),
+            ty: ty.into(),
+            default: None,
+        }
+    }
+
+    /// Add a lifetime that a lifetime parameter outlives. Has no effect on
+    /// other parameters.
+    pub fn outlives<S>(mut self, lifetime: S) -> Self
+    where
+        S: ToString,
+    {
+        if let GenericParam::Lifetime { ref mut outlives, .. } = self {
+            outlives.push(lifetime.to_string());
+        }
+        self
+    }
+
+    /// Add a bound to a type parameter. Has no effect on other parameters.
+    pub fn bound<B>(mut self, bound: B) -> Self
+    where
+        B: Into<TypeBound>,
+    {
+        if let GenericParam::Type { ref mut bounds, .. } = self {
+            bounds.push(bound.into());
+        }
+        self
+    }
+
+    /// Set the default of a type parameter. Has no effect on other
+    /// parameters.
+    pub fn default<T>(mut self, ty: T) -> Self
+    where
+        T: Into<Type>,
+    {
+        if let GenericParam::Type { ref mut default, .. } = self {
+            *default = Some(ty.into());
+        }
+        self
+    }
+
+    /// Set the default value of a const parameter. Has no effect on other
+    /// parameters.
+    pub fn default_value<S>(mut self, value: S) -> Self
+    where
+        S: ToString,
+    {
+        if let GenericParam::Const { ref mut default, .. } = self {
+            *default = Some(value.to_string());
+        }
+        self
+    }
+
+    /// Returns the name of the parameter.
+    pub fn name(&self) -> &str {
+        match self {
+            GenericParam::Lifetime { name, .. }
+            | GenericParam::Type { name, .. }
+            | GenericParam::Const { name, .. } => name,
+        }
+    }
+
+    /// Checks if this is a lifetime parameter.
+    pub fn is_lifetime(&self) -> bool {
+        matches!(self, GenericParam::Lifetime { .. })
+    }
+
+    /// Returns the parameter without its default, as it has to be declared
+    /// on an impl block.
+    pub(crate) fn without_default(&self) -> Self {
+        let mut param = self.clone();
+
+        match param {
+            GenericParam::Type { ref mut default, .. } => *default = None,
+            GenericParam::Const { ref mut default, .. } => *default = None,
+            GenericParam::Lifetime { .. } => {}
+        }
+
+        param
+    }
+
+    /// Where the parameter has to appear in a parameter list: lifetimes
+    /// come first, and parameters with defaults come last.
+    fn order(&self) -> u8 {
+        match self {
+            GenericParam::Lifetime { .. } => 0,
+            GenericParam::Type { default: None, .. }
+            | GenericParam::Const { default: None, .. } => 1,
+            _ => 2,
+        }
+    }
+
+    /// Formats the parameter using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GenericParam::Lifetime { name, outlives } => {
+                write!(fmt, "{}", name)?;
+
+                for (i, lifetime) in outlives.iter().enumerate() {
+                    write!(fmt, "{}{}", if i == 0 { ": " } else { " + " }, lifetime)?;
+                }
+
+                Ok(())
+            }
+            GenericParam::Type { name, bounds, default } => {
+                write!(fmt, "{}", name)?;
+
+                for (i, bound) in bounds.iter().enumerate() {
+                    write!(fmt, "{}", if i == 0 { ": " } else { " + " })?;
+                    bound.fmt(fmt)?;
+                }
+
+                if let Some(default) = default {
+                    write!(fmt, " = ")?;
+                    default.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+            GenericParam::Const { name, ty, default } => {
+                write!(fmt, "const {}: ", name)?;
+                ty.fmt(fmt)?;
+
+                if let Some(default) = default {
+                    write!(fmt, " = {}", default)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for GenericParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        GenericParam::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
+
+impl<S> From<S> for GenericParam
+where
+    S: AsRef<str>
+{
+    fn from(src: S) -> Self {
+        let src = src.as_ref();
+
+        Parser::new(src)
+            .and_then(|mut parser| {
+                let param = parser.parse_generic_param()?;
+                parser.finish()?;
+                Ok(param)
+            })
+            .unwrap_or_else(|_| GenericParam::type_param(src))
+    }
+}
+
+impl From<Type> for GenericParam {
+    fn from(src: Type) -> Self {
+        GenericParam::type_param(src)
+    }
+}
+
+impl<'a> From<&'a GenericParam> for GenericParam {
+    fn from(src: &'a GenericParam) -> Self {
+        src.clone()
+    }
 }
 
 impl Generics {
     pub fn new() -> Self {
         Self {
-            lifetimes: vec![],
-            generics: vec![],
+            params: vec![],
         }
     }
 
@@ -22,7 +266,7 @@ impl Generics {
     where
         S: AsRef<str>
     {
-        self.lifetimes.push(lifetime.as_ref().to_owned());
+        self.params.push(lifetime.into());
         self
     }
 
@@ -31,62 +275,72 @@ impl Generics {
         I: IntoIterator<Item = S>,
         S: AsRef<str>
     {
-        self.lifetimes.extend(lifetimes.into_iter().map(|l| l.as_ref().to_owned()));
+        self.params.extend(lifetimes.into_iter().map(Into::into));
         self
     }
 
     pub fn clear_lifetimes(&mut self) -> &mut Self {
-        self.lifetimes.clear();
+        self.params.retain(|param| !param.is_lifetime());
         self
     }
 
     pub fn push_generic<T>(&mut self, generic: T) -> &mut Self
     where
-        T: Into<Type>
+        T: Into<GenericParam>
     {
-        self.generics.push(generic.into());
+        self.params.push(generic.into());
         self
     }
 
     pub fn extend_generics<I, T>(&mut self, generics: I) -> &mut Self
     where
         I: IntoIterator<Item = T>,
-        T: Into<Type>
+        T: Into<GenericParam>
     {
-        self.generics.extend(generics.into_iter().map(|t| t.into()));
+        self.params.extend(generics.into_iter().map(Into::into));
         self
     }
 
     pub fn clear_generics(&mut self) -> &mut Self {
-        self.generics.clear();
+        self.params.retain(GenericParam::is_lifetime);
         self
     }
 
-    /// Format generics.
-    pub(crate) fn fmt_generics(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        if !self.lifetimes.is_empty() || !self.generics.is_empty() {
-            write!(fmt, "<")?;
+    /// Returns the parameters, in the order they were added.
+    pub(crate) fn params(&self) -> &[GenericParam] {
+        &self.params
+    }
 
-            for (idx, lifetime) in self.lifetimes.iter().enumerate() {
-                write!(fmt, "{}", lifetime)?;
+    /// Returns the names of the parameters.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.params.iter().map(GenericParam::name)
+    }
 
-                if !self.generics.is_empty() || idx != self.lifetimes.len() - 1 {
-                    write!(fmt, ", ")?;
-                }
-            }
+    /// Reports generic parameters that are declared twice.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        check_duplicates(self.names(), "generic parameter", |_| path.to_owned(), diags);
+    }
+
+    /// Format generics, with lifetimes first and defaulted parameters last
+    /// as rustc requires.
+    pub(crate) fn fmt_generics(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if self.params.is_empty() {
+            return Ok(());
+        }
 
-            for (idx, generic) in self.generics.iter().enumerate() {
-                generic.fmt(fmt)?;
+        let mut params: Vec<_> = self.params.iter().collect();
+        params.sort_by_key(|param| param.order());
 
-                if idx != self.generics.len() - 1 {
-                    write!(fmt, ", ")?;
-                }
-            }
+        write!(fmt, "<")?;
 
-            write!(fmt, ">")?;
+        for (idx, param) in params.iter().enumerate() {
+            if idx != 0 {
+                write!(fmt, ", ")?;
+            }
+            param.fmt(fmt)?;
         }
 
-        Ok(())
+        write!(fmt, ">")
     }
 }
 
@@ -125,26 +379,27 @@ macro_rules! impl_generic_methods {
             self
         }
 
-        /// Pushes a generic type to the inner container
+        /// Pushes a generic parameter, like `T: Clone` or `const N: usize`,
+        /// to the inner container
         pub fn push_generic<T>(&mut self, generic: T) -> &mut Self
         where
-            T: Into<$crate::type_def::Type>
+            T: Into<$crate::generics::GenericParam>
         {
             self.$($inner)+.push_generic(generic);
             self
         }
 
-        /// Extends multiple generic types to the inner container
+        /// Extends multiple generic parameters to the inner container
         pub fn extend_generics<I, T>(&mut self, generics: I) -> &mut Self
         where
             I: IntoIterator<Item = T>,
-            T: Into<$crate::type_def::Type>
+            T: Into<$crate::generics::GenericParam>
         {
             self.$($inner)+.extend_generics(generics);
             self
         }
 
-        /// Clears all generics from the inner container.
+        /// Clears all type and const parameters from the inner container.
         pub fn clear_generics(&mut self) -> &mut Self {
             self.$($inner)+.clear_generics();
             self
@@ -153,3 +408,51 @@ macro_rules! impl_generic_methods {
 }
 
 pub(crate) use impl_generic_methods;
+
+impl Visit for GenericParam {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        match self {
+            GenericParam::Lifetime { .. } => {}
+            GenericParam::Type { bounds, default, .. } => {
+                for bound in bounds {
+                    bound.visit_types(f);
+                }
+
+                if let Some(default) = default {
+                    f(default);
+                }
+            }
+            GenericParam::Const { ty, .. } => f(ty),
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        match self {
+            GenericParam::Lifetime { .. } => {}
+            GenericParam::Type { bounds, default, .. } => {
+                for bound in bounds {
+                    bound.visit_types_mut(f);
+                }
+
+                if let Some(default) = default {
+                    f(default);
+                }
+            }
+            GenericParam::Const { ty, .. } => f(ty),
+        }
+    }
+}
+
+impl Visit for Generics {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        for param in &self.params {
+            param.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        for param in &mut self.params {
+            param.visit_types_mut(f);
+        }
+    }
+}
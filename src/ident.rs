@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::fmt;
+
+
+/// Keywords reserved in every edition.
+const KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
+    "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Keywords reserved since the 2018 edition.
+const KEYWORDS_2018: &[&str] = &["async", "await", "dyn", "try"];
+
+/// Keywords reserved since the 2024 edition.
+const KEYWORDS_2024: &[&str] = &["gen"];
+
+/// Keywords that can't be used even as raw identifiers.
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+
+/// A Rust edition, which determines the set of reserved keywords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[non_exhaustive]
+pub enum Edition {
+    /// The 2015 edition.
+    E2015,
+    /// The 2018 edition.
+    E2018,
+    /// The 2021 edition. The default.
+    #[default]
+    E2021,
+    /// The 2024 edition.
+    E2024,
+}
+
+/// Whether or not `name` is a reserved keyword in the given edition.
+pub fn is_keyword(name: &str, edition: Edition) -> bool {
+    KEYWORDS.contains(&name)
+        || (edition >= Edition::E2018 && KEYWORDS_2018.contains(&name))
+        || (edition >= Edition::E2024 && KEYWORDS_2024.contains(&name))
+}
+
+
+/// An error returned when a string can't be made into an [`Ident`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentError {
+    name: String,
+    msg: &'static str,
+}
+
+impl IdentError {
+    /// Returns the string that was rejected.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns a description of why the string was rejected.
+    pub fn message(&self) -> &str {
+        self.msg
+    }
+}
+
+impl fmt::Display for IdentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {}", self.name, self.msg)
+    }
+}
+
+impl Error for IdentError {}
+
+
+/// A checked identifier, such as a field or variant name.
+///
+/// Keywords are escaped as raw identifiers, so `type` becomes `r#type`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ident(String);
+
+impl Ident {
+    /// Returns a new identifier, escaping keywords of the default edition.
+    ///
+    /// Returns an error if `name` isn't a valid identifier, or is a keyword
+    /// that can't be used as a raw identifier, like `self`.
+    pub fn new<S>(name: S) -> Result<Self, IdentError>
+    where
+        S: AsRef<str>
+    {
+        Self::new_with_edition(name, Edition::default())
+    }
+
+    /// Returns a new identifier, escaping the keywords of the given edition.
+    pub fn new_with_edition<S>(name: S, edition: Edition) -> Result<Self, IdentError>
+    where
+        S: AsRef<str>
+    {
+        let name = name.as_ref();
+        let error = |msg| Err(IdentError { name: name.to_owned(), msg });
+
+        // Already escaped names are accepted as is.
+        let (unraw, is_raw) = match name.strip_prefix("r#") {
+            Some(unraw) => (unraw, true),
+            None => (name, false),
+        };
+
+        if !is_ident(unraw) {
+            return error("is not a valid identifier");
+        }
+
+        if PATH_KEYWORDS.contains(&unraw) {
+            return error("can not be used as a raw identifier");
+        }
+
+        if !is_raw && is_keyword(unraw, edition) {
+            Ok(Ident(format!("r#{}", unraw)))
+        } else {
+            Ok(Ident(name.to_owned()))
+        }
+    }
+
+    /// Maps an arbitrary string to a valid identifier, using the keywords of
+    /// the default edition.
+    ///
+    /// Characters that can't appear in identifiers are replaced with `_`, a
+    /// leading digit is prefixed with `_`, keywords are escaped, and keywords
+    /// that can't be raw identifiers get a trailing `_`. For example,
+    /// `foo-bar` becomes `foo_bar`, `123abc` becomes `_123abc` and `self`
+    /// becomes `self_`. An empty string becomes `_empty`.
+    pub fn sanitize<S>(name: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        Self::sanitize_with_edition(name, Edition::default())
+    }
+
+    /// Maps an arbitrary string to a valid identifier, using the keywords of
+    /// the given edition.
+    pub fn sanitize_with_edition<S>(name: S, edition: Edition) -> Self
+    where
+        S: AsRef<str>
+    {
+        let name = name.as_ref();
+
+        let mut sanitized: String = name.chars()
+            .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+            .collect();
+
+        if sanitized.is_empty() {
+            sanitized.push_str("_empty");
+        } else if sanitized == "_" {
+            sanitized.push('_');
+        } else if sanitized.starts_with(|c: char| c.is_numeric()) {
+            sanitized.insert(0, '_');
+        }
+
+        if PATH_KEYWORDS.contains(&sanitized.as_str()) {
+            sanitized.push('_');
+        }
+
+        Self::new_with_edition(sanitized, edition)
+            .expect("sanitized identifiers are always valid")
+    }
+
+    /// Returns the identifier as it is written in code, e.g. `r#type`.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Returns the identifier without the raw `r#` prefix, e.g. `type`.
+    pub fn unraw(&self) -> &str {
+        self.0.strip_prefix("r#").unwrap_or(&self.0)
+    }
+
+    /// Whether or not this is a raw identifier, like `r#type`.
+    pub fn is_raw(&self) -> bool {
+        self.0.starts_with("r#")
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Ident {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<Ident> for String {
+    fn from(ident: Ident) -> Self {
+        ident.0
+    }
+}
+
+/// Whether or not `name` has the shape of an identifier, ignoring keywords.
+pub(crate) fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && name != "_"
+}
@@ -2,8 +2,12 @@ use std::fmt::{self, Write};
 
 use crate::attributes::Attributes;
 use crate::bounds::Bounds;
+use crate::const_gen::Const;
 use crate::field::Field;
+use crate::error::CodegenError;
 use crate::formatter::Formatter;
+use crate::validate::{Diagnostic, check_duplicates, member_path};
+use crate::visit::{Text, Visit};
 use crate::function::Function;
 use crate::generics::Generics;
 use crate::type_def::Type;
@@ -15,7 +19,7 @@ use crate::impl_macros::{
 };
 
 /// Defines an impl block.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Impl {
     /// The struct being implemented
     target: Type,
@@ -29,6 +33,9 @@ pub struct Impl {
     /// Associated types
     assoc_tys: Vec<Field>,
 
+    /// Associated constants
+    consts: Vec<Const>,
+
     /// Bounds
     bounds: Bounds,
 
@@ -48,6 +55,7 @@ impl Impl {
             generics: Generics::default(),
             impl_trait: None,
             assoc_tys: vec![],
+            consts: vec![],
             bounds: Bounds::default(),
             fns: vec![],
             attrs: Attributes::default(),
@@ -72,13 +80,36 @@ impl Impl {
         self
     }
 
-    /// Set an associated type.
+    /// Set an associated type, replacing any type already set for the same
+    /// name.
     pub fn associate_type<S, T>(&mut self, name: S, ty: T) -> &mut Self
     where
         S: AsRef<str>,
         T: Into<Type>,
     {
-        self.assoc_tys.push(Field::new_named(name, ty));
+        let field = Field::new_named(name, ty);
+
+        match self.assoc_tys.iter_mut().find(|assoc_ty| assoc_ty.name() == field.name()) {
+            Some(assoc_ty) => *assoc_ty = field,
+            None => self.assoc_tys.push(field),
+        }
+
+        self
+    }
+
+    /// Push a new associated constant, returning a mutable reference to it.
+    pub fn new_const<S, T>(&mut self, name: S, ty: T) -> &mut Const
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.push_const(Const::new(name, ty));
+        self.consts.last_mut().unwrap()
+    }
+
+    /// Push an associated constant.
+    pub fn push_const(&mut self, item: Const) -> &mut Self {
+        self.consts.push(item);
         self
     }
 
@@ -94,6 +125,32 @@ impl Impl {
         self
     }
 
+    /// Reports problems with the impl block's generics and items.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        self.generics.validate(path, diags);
+        validate_assoc_items(&self.consts, &self.fns, path, diags);
+    }
+
+    /// Returns the name of the target type, used in error paths.
+    pub(crate) fn target_name(&self) -> &str {
+        self.target.name().unwrap_or_default()
+    }
+
+    /// Checks that the impl block can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        let name = self.target_name();
+
+        for item in &self.consts {
+            item.check(false).map_err(|err| err.in_item(name))?;
+        }
+
+        for func in &self.fns {
+            func.check(false).map_err(|err| err.in_item(name))?;
+        }
+
+        Ok(())
+    }
+
     /// Formats the impl block using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.attrs.fmt_attrs(fmt)?;
@@ -120,9 +177,13 @@ impl Impl {
                 }
             }
 
+            for constant in &self.consts {
+                constant.fmt(false, fmt)?;
+            }
+
             for (i, func) in self.fns.iter().enumerate() {
-                if i != 0 || !self.assoc_tys.is_empty() {
-                    write!(fmt, "\n")?;
+                if i != 0 || !self.assoc_tys.is_empty() || !self.consts.is_empty() {
+                    writeln!(fmt)?;
                 }
 
                 func.fmt(false, fmt)?;
@@ -136,3 +197,87 @@ impl Impl {
     impl_bounds_methods!(bounds);
     impl_generic_methods!(generics);
 }
+
+impl Visit for Impl {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        f(&self.target);
+        self.generics.visit_types(f);
+
+        if let Some(impl_trait) = &self.impl_trait {
+            f(impl_trait);
+        }
+
+        for assoc_ty in &self.assoc_tys {
+            assoc_ty.visit_types(f);
+        }
+
+        for item in &self.consts {
+            item.visit_types(f);
+        }
+
+        self.bounds.visit_types(f);
+
+        for func in &self.fns {
+            func.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        f(&mut self.target);
+        self.generics.visit_types_mut(f);
+
+        if let Some(impl_trait) = &mut self.impl_trait {
+            f(impl_trait);
+        }
+
+        for assoc_ty in &mut self.assoc_tys {
+            assoc_ty.visit_types_mut(f);
+        }
+
+        for item in &mut self.consts {
+            item.visit_types_mut(f);
+        }
+
+        self.bounds.visit_types_mut(f);
+
+        for func in &mut self.fns {
+            func.visit_types_mut(f);
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+
+        for assoc_ty in &self.assoc_tys {
+            assoc_ty.visit_text(f);
+        }
+
+        for item in &self.consts {
+            item.visit_text(f);
+        }
+
+        for func in &self.fns {
+            func.visit_text(f);
+        }
+    }
+}
+
+/// Reports problems with the consts and fns of an impl block or trait at
+/// `path`.
+pub(crate) fn validate_assoc_items(
+    consts: &[Const],
+    fns: &[Function],
+    path: &str,
+    diags: &mut Vec<Diagnostic>,
+) {
+    for item in consts {
+        item.validate(&member_path(path, item.name()), diags);
+    }
+
+    for func in fns {
+        func.validate(&member_path(path, func.name()), diags);
+    }
+
+    let names = consts.iter().map(Const::name).chain(fns.iter().map(Function::name));
+    check_duplicates(names, "associated item", |name| member_path(path, name), diags);
+}
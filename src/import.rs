@@ -1,11 +1,234 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::Formatter;
+use crate::parse::Parser;
 use crate::vis::Vis;
 
 use crate::impl_macros::impl_vis_methods;
 
+
+/// A tree of paths in a `use` declaration, e.g. the `foo::{self, bar::{A, B}}`
+/// in `use foo::{self, bar::{A, B}};`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UseTree {
+    /// A path segment followed by the rest of the tree, e.g. `foo::...`.
+    Path(String, Box<UseTree>),
+    /// A single imported name, e.g. `Bar` or `self`.
+    Name(String),
+    /// An imported name with an alias, e.g. `Bar as Baz`.
+    Rename(String, String),
+    /// A glob import, `*`.
+    Glob,
+    /// A group of trees, e.g. `{A, b::C}`.
+    Group(Vec<UseTree>),
+}
+
+impl UseTree {
+    /// Returns a tree importing the given path, e.g. `std::io::Read`.
+    pub fn name<S>(path: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        let (prefix, name) = split_last(path.as_ref());
+        Self::prefixed(prefix, UseTree::Name(name.to_owned()))
+    }
+
+    /// Returns a tree importing the given path under an alias, e.g.
+    /// `std::io::Result as IoResult`.
+    pub fn rename<S, A>(path: S, alias: A) -> Self
+    where
+        S: AsRef<str>,
+        A: AsRef<str>,
+    {
+        let (prefix, name) = split_last(path.as_ref());
+        Self::prefixed(prefix, UseTree::Rename(name.to_owned(), alias.as_ref().to_owned()))
+    }
+
+    /// Returns a tree importing everything from the given path, e.g.
+    /// `std::io::prelude::*`.
+    pub fn glob<S>(path: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        Self::prefixed(path.as_ref(), UseTree::Glob)
+    }
+
+    /// Returns a tree importing a group of trees under the given path, e.g.
+    /// `std::io::{self, Read}`.
+    pub fn group<S, I, T>(path: S, trees: I) -> Self
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = T>,
+        T: Into<UseTree>,
+    {
+        let group = UseTree::Group(trees.into_iter().map(Into::into).collect());
+        Self::prefixed(path.as_ref(), group)
+    }
+
+    /// Nests `tree` under each `::` separated segment of `prefix`.
+    fn prefixed(prefix: &str, tree: UseTree) -> Self {
+        if prefix.is_empty() {
+            return tree;
+        }
+
+        prefix.rsplit("::")
+            .fold(tree, |tree, segment| UseTree::Path(segment.to_owned(), Box::new(tree)))
+    }
+
+    /// Returns the name this tree brings into scope, if it imports exactly
+    /// one name.
+    pub fn imported_name(&self) -> Option<&str> {
+        match self {
+            UseTree::Path(_, tree) => tree.imported_name(),
+            UseTree::Name(name) => Some(name.as_str()),
+            UseTree::Rename(_, alias) => Some(alias.as_str()),
+            UseTree::Glob | UseTree::Group(_) => None,
+        }
+    }
+
+    /// Returns the tree with only the leaves whose imported name satisfies
+    /// `keep`, or `None` if no leaves remain. Globs and `_` imports are
+    /// always kept.
+    pub(crate) fn retain_names(&self, keep: &dyn Fn(&str) -> bool) -> Option<UseTree> {
+        self.retain_names_in(None, keep)
+    }
+
+    fn retain_names_in(&self, parent: Option<&str>, keep: &dyn Fn(&str) -> bool) -> Option<UseTree> {
+        match self {
+            UseTree::Path(segment, tree) => tree.retain_names_in(Some(segment), keep)
+                .map(|tree| UseTree::Path(segment.clone(), Box::new(tree))),
+            UseTree::Name(name) => {
+                // `foo::{self}` imports `foo`
+                let imported = match parent {
+                    Some(parent) if name == "self" => parent,
+                    _ => name.as_str(),
+                };
+
+                keep(imported).then(|| self.clone())
+            }
+            UseTree::Rename(_, alias) => (alias == "_" || keep(alias)).then(|| self.clone()),
+            UseTree::Glob => Some(self.clone()),
+            UseTree::Group(trees) => {
+                let trees: Vec<UseTree> = trees.iter()
+                    .filter_map(|tree| tree.retain_names_in(parent, keep))
+                    .collect();
+
+                (!trees.is_empty()).then_some(UseTree::Group(trees))
+            }
+        }
+    }
+
+    /// Flattens the tree into a list of module paths and the leaf imported
+    /// from each of them, e.g. `a::{b::C, D}` flattens into `(a::b, C)` and
+    /// `(a, D)`.
+    pub(crate) fn flatten(&self) -> Vec<(Vec<&str>, &UseTree)> {
+        let mut leaves = vec![];
+        self.flatten_into(&mut vec![], &mut leaves);
+        leaves
+    }
+
+    fn flatten_into<'a>(
+        &'a self,
+        prefix: &mut Vec<&'a str>,
+        leaves: &mut Vec<(Vec<&'a str>, &'a UseTree)>,
+    ) {
+        match self {
+            UseTree::Path(segment, tree) => {
+                prefix.push(segment.as_str());
+                tree.flatten_into(prefix, leaves);
+                prefix.pop();
+            }
+            UseTree::Group(trees) => {
+                for tree in trees {
+                    tree.flatten_into(prefix, leaves);
+                }
+            }
+            leaf => leaves.push((prefix.clone(), leaf)),
+        }
+    }
+
+    /// Formats the tree using the given formatter.
+    pub(crate) fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UseTree::Path(segment, tree) => {
+                write!(fmt, "{}::", segment)?;
+                tree.fmt(fmt)
+            }
+            UseTree::Name(name) => write!(fmt, "{}", name),
+            UseTree::Rename(name, alias) => write!(fmt, "{} as {}", name, alias),
+            UseTree::Glob => write!(fmt, "*"),
+            UseTree::Group(trees) => {
+                write!(fmt, "{{")?;
+
+                for (i, tree) in trees.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, ", ")?;
+                    }
+                    tree.fmt(fmt)?;
+                }
+
+                write!(fmt, "}}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for UseTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        self.fmt(&mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
+
+impl<S> From<S> for UseTree
+where
+    S: AsRef<str>
+{
+    /// Parses a tree from a string, such as `foo::{self, bar::{A, B}}`.
+    /// Strings that don't parse are imported verbatim.
+    fn from(src: S) -> Self {
+        let src = src.as_ref();
+
+        Parser::new(src)
+            .and_then(|mut parser| {
+                let tree = parser.parse_use_tree()?;
+                parser.finish()?;
+                Ok(tree)
+            })
+            .unwrap_or_else(|_| UseTree::Name(src.to_owned()))
+    }
+}
+
+fn split_last(path: &str) -> (&str, &str) {
+    match path.rsplit_once("::") {
+        Some((prefix, name)) => (prefix, name),
+        None => ("", path),
+    }
+}
+
+
+/// The order in which the `use` statements of a scope are formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportOrder {
+    /// Imports are formatted in the order they were added. The default.
+    #[default]
+    Insertion,
+    /// Imports are sorted alphabetically by path.
+    Alphabetical,
+    /// Imports are sorted alphabetically and split into groups separated by
+    /// blank lines: `std`, `core` and `alloc` first, then external crates,
+    /// then `crate`, `super` and `self`. Within a group, private imports come
+    /// before re-exports. This matches rustfmt's
+    /// `group_imports = "StdExternalCrate"`.
+    Grouped,
+}
+
+
 /// Defines an import (`use` statement).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Import {
-    line: String,
+    tree: UseTree,
 
     /// Function visibility
     pub vis: Vis,
@@ -17,12 +240,93 @@ impl Import {
     where
         S: AsRef<str>,
         T: AsRef<str>,
+    {
+        Self::new_tree(UseTree::name(format!("{}::{}", path.as_ref(), ty.as_ref())))
+    }
+
+    /// Return a new import of the given tree.
+    pub fn new_tree<T>(tree: T) -> Self
+    where
+        T: Into<UseTree>,
     {
         Import {
-            line: format!("{}::{}", path.as_ref(), ty.as_ref()),
+            tree: tree.into(),
             vis: Vis::default(),
         }
     }
 
+    /// Returns the tree being imported.
+    pub fn tree(&self) -> &UseTree {
+        &self.tree
+    }
+
+    /// Removes the leaves of the tree whose imported name doesn't satisfy
+    /// `keep`, returning whether anything is left to import.
+    pub(crate) fn retain_names(&mut self, keep: &dyn Fn(&str) -> bool) -> bool {
+        match self.tree.retain_names(keep) {
+            Some(tree) => {
+                self.tree = tree;
+                true
+            }
+            None => false,
+        }
+    }
+
+    impl_vis_methods!(field => vis);
+}
+
+
+/// Defines an `extern crate` declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternCrate {
+    name: String,
+    alias: Option<String>,
+    vis: Vis,
+}
+
+impl ExternCrate {
+    /// Return a new `extern crate` declaration for the given crate.
+    pub fn new<S>(name: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        ExternCrate {
+            name: name.as_ref().to_owned(),
+            alias: None,
+            vis: Vis::default(),
+        }
+    }
+
+    /// Returns the name of the crate.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Import the crate under an alias, i.e. `extern crate foo as bar;`.
+    pub fn alias<S>(&mut self, alias: S) -> &mut Self
+    where
+        S: AsRef<str>
+    {
+        self.alias = Some(alias.as_ref().to_owned());
+        self
+    }
+
+    /// Returns the name the crate is imported under.
+    pub(crate) fn imported_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Formats the declaration using the given formatter.
+    pub(crate) fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.vis.fmt(fmt)?;
+        write!(fmt, "extern crate {}", self.name)?;
+
+        if let Some(ref alias) = self.alias {
+            write!(fmt, " as {}", alias)?;
+        }
+
+        writeln!(fmt, ";")
+    }
+
     impl_vis_methods!(field => vis);
 }
@@ -1,17 +1,269 @@
+use std::fmt::{self, Write};
+
+use crate::const_gen::Const;
 use crate::enum_gen::Enum;
+use crate::error::CodegenError;
+use crate::extern_block::ExternBlock;
+use crate::formatter::Formatter;
 use crate::function::Function;
 use crate::impl_gen::Impl;
 use crate::module::Module;
+use crate::static_gen::Static;
 use crate::struct_gen::Struct;
 use crate::trait_gen::Trait;
+use crate::type_alias::TypeAlias;
+use crate::type_def::Type;
+use crate::union_gen::Union;
+use crate::validate::{Diagnostic, Severity, item_path};
+use crate::visit::{Text, Visit};
 
-#[derive(Debug, Clone)]
+/// An item, defined in a scope or nested in a function body.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Item {
+    /// A module.
     Module(Module),
+    /// A struct.
     Struct(Struct),
+    /// A function.
     Function(Function),
+    /// A trait.
     Trait(Trait),
+    /// An enum.
     Enum(Enum),
+    /// An impl block.
     Impl(Impl),
+    /// A `const`.
+    Const(Const),
+    /// A `static`.
+    Static(Static),
+    /// A type alias.
+    TypeAlias(TypeAlias),
+    /// A union.
+    Union(Union),
+    /// An `extern` block.
+    ExternBlock(ExternBlock),
+    /// Source text that is written as is.
     Raw(String),
 }
+
+impl Item {
+    /// Formats the item using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Item::Module(ref v) => v.fmt(fmt),
+            Item::Struct(ref v) => v.fmt(fmt),
+            Item::Function(ref v) => v.fmt(false, fmt),
+            Item::Trait(ref v) => v.fmt(fmt),
+            Item::Enum(ref v) => v.fmt(fmt),
+            Item::Impl(ref v) => v.fmt(fmt),
+            Item::Const(ref v) => v.fmt(false, fmt),
+            Item::Static(ref v) => v.fmt(fmt),
+            Item::TypeAlias(ref v) => v.fmt(fmt),
+            Item::Union(ref v) => v.fmt(fmt),
+            Item::ExternBlock(ref v) => v.fmt(fmt),
+            Item::Raw(ref v) => writeln!(fmt, "{}", v),
+        }
+    }
+
+    /// Checks that the item can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        match self {
+            Item::Module(v) => v.check(),
+            Item::Struct(v) => v.check(),
+            Item::Function(v) => v.check(false),
+            Item::Trait(v) => v.check(),
+            Item::Enum(v) => v.check(),
+            Item::Impl(v) => v.check(),
+            Item::Const(v) => v.check(false),
+            Item::Static(v) => v.check(),
+            Item::Union(v) => v.check(),
+            Item::ExternBlock(v) => v.check(),
+            Item::TypeAlias(_) | Item::Raw(_) => Ok(()),
+        }
+    }
+
+    /// Reports problems with the item, which is defined in `module`.
+    pub(crate) fn validate(&self, module: &str, diags: &mut Vec<Diagnostic>) {
+        let path = |name: &str| item_path(module, name);
+
+        match self {
+            Item::Module(v) => v.validate(&path(v.name()), diags),
+            Item::Struct(v) => v.validate(&path(v.ty().name().unwrap_or_default()), diags),
+            Item::Function(v) => v.validate(&path(v.name()), diags),
+            Item::Trait(v) => v.validate(&path(v.ty().name().unwrap_or_default()), diags),
+            Item::Enum(v) => v.validate(&path(v.ty().name().unwrap_or_default()), diags),
+            Item::Impl(v) => v.validate(&path(v.target_name()), diags),
+            Item::Const(v) => v.validate(&path(v.name()), diags),
+            Item::Static(v) => v.validate(&path(v.name()), diags),
+            Item::TypeAlias(v) => v.validate(&path(v.ty().name().unwrap_or_default()), diags),
+            Item::Union(v) => v.validate(&path(v.ty().name().unwrap_or_default()), diags),
+            Item::ExternBlock(v) => v.validate(module, diags),
+            Item::Raw(_) => {}
+        }
+    }
+
+    /// Reports the types used by the item, including those in its function
+    /// bodies, that were kept verbatim because they don't parse.
+    pub(crate) fn validate_types(&self, module: &str, diags: &mut Vec<Diagnostic>) {
+        let path = match self {
+            Item::Module(_) | Item::Raw(_) => return,
+            Item::ExternBlock(_) => module.to_owned(),
+            Item::Impl(v) => item_path(module, v.target_name()),
+            item => item_path(module, item.type_name().or(item.value_name()).unwrap_or_default()),
+        };
+
+        self.visit_types(&mut |ty| {
+            if let Type::Verbatim(src) = ty {
+                if Type::parse(src).is_err() {
+                    diags.push(Diagnostic::new(
+                        Severity::Warning,
+                        &path,
+                        format!("`{}` is not a valid type, so it is written as is", src),
+                    ));
+                }
+            }
+        });
+    }
+
+    /// Returns the name the item defines in the value namespace, if any.
+    pub(crate) fn value_name(&self) -> Option<&str> {
+        match self {
+            Item::Function(v) => Some(v.name()),
+            Item::Const(v) => Some(v.name()),
+            Item::Static(v) => Some(v.name()),
+            _ => None,
+        }
+    }
+
+    /// Returns the name the item defines in the type namespace, if any.
+    pub(crate) fn type_name(&self) -> Option<&str> {
+        match self {
+            Item::Module(v) => Some(v.name()),
+            Item::Struct(v) => v.ty().name(),
+            Item::Trait(v) => v.ty().name(),
+            Item::Enum(v) => v.ty().name(),
+            Item::TypeAlias(v) => v.ty().name(),
+            Item::Union(v) => v.ty().name(),
+            _ => None,
+        }
+    }
+}
+
+impl From<Module> for Item {
+    fn from(src: Module) -> Self {
+        Item::Module(src)
+    }
+}
+
+impl From<Struct> for Item {
+    fn from(src: Struct) -> Self {
+        Item::Struct(src)
+    }
+}
+
+impl From<Function> for Item {
+    fn from(src: Function) -> Self {
+        Item::Function(src)
+    }
+}
+
+impl From<Trait> for Item {
+    fn from(src: Trait) -> Self {
+        Item::Trait(src)
+    }
+}
+
+impl From<Enum> for Item {
+    fn from(src: Enum) -> Self {
+        Item::Enum(src)
+    }
+}
+
+impl From<Impl> for Item {
+    fn from(src: Impl) -> Self {
+        Item::Impl(src)
+    }
+}
+
+impl From<Const> for Item {
+    fn from(src: Const) -> Self {
+        Item::Const(src)
+    }
+}
+
+impl From<Static> for Item {
+    fn from(src: Static) -> Self {
+        Item::Static(src)
+    }
+}
+
+impl From<TypeAlias> for Item {
+    fn from(src: TypeAlias) -> Self {
+        Item::TypeAlias(src)
+    }
+}
+
+impl From<Union> for Item {
+    fn from(src: Union) -> Self {
+        Item::Union(src)
+    }
+}
+
+impl From<ExternBlock> for Item {
+    fn from(src: ExternBlock) -> Self {
+        Item::ExternBlock(src)
+    }
+}
+
+impl Visit for Item {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        match self {
+            Item::Struct(v) => v.visit_types(f),
+            Item::Function(v) => v.visit_types(f),
+            Item::Trait(v) => v.visit_types(f),
+            Item::Enum(v) => v.visit_types(f),
+            Item::Impl(v) => v.visit_types(f),
+            Item::Const(v) => v.visit_types(f),
+            Item::Static(v) => v.visit_types(f),
+            Item::TypeAlias(v) => v.visit_types(f),
+            Item::Union(v) => v.visit_types(f),
+            Item::ExternBlock(v) => v.visit_types(f),
+            Item::Module(v) => v.visit_types(f),
+            Item::Raw(_) => {}
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        match self {
+            Item::Struct(v) => v.visit_types_mut(f),
+            Item::Function(v) => v.visit_types_mut(f),
+            Item::Trait(v) => v.visit_types_mut(f),
+            Item::Enum(v) => v.visit_types_mut(f),
+            Item::Impl(v) => v.visit_types_mut(f),
+            Item::Const(v) => v.visit_types_mut(f),
+            Item::Static(v) => v.visit_types_mut(f),
+            Item::TypeAlias(v) => v.visit_types_mut(f),
+            Item::Union(v) => v.visit_types_mut(f),
+            Item::ExternBlock(v) => v.visit_types_mut(f),
+            Item::Module(v) => v.visit_types_mut(f),
+            Item::Raw(_) => {}
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        match self {
+            Item::Module(v) => v.visit_text(f),
+            Item::Struct(v) => v.visit_text(f),
+            Item::Function(v) => v.visit_text(f),
+            Item::Trait(v) => v.visit_text(f),
+            Item::Enum(v) => v.visit_text(f),
+            Item::Impl(v) => v.visit_text(f),
+            Item::Const(v) => v.visit_text(f),
+            Item::Static(v) => v.visit_text(f),
+            Item::TypeAlias(v) => v.visit_text(f),
+            Item::Union(v) => v.visit_text(f),
+            Item::ExternBlock(v) => v.visit_text(f),
+            Item::Raw(v) => f(Text::Verbatim, v),
+        }
+    }
+}
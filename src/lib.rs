@@ -23,46 +23,88 @@
 //! println!("{}", scope.to_string());
 //! ```
 
+mod arg;
 mod associated_type;
 mod attributes;
 mod block;
-mod body;
 mod bounds;
+mod const_gen;
+mod control_flow;
 mod docs;
 mod enum_gen;
+mod error;
+mod expr;
+mod extern_block;
 mod field;
 mod fields;
 mod formatter;
 mod function;
 mod generics;
+mod ident;
 mod impl_gen;
 mod import;
 mod item;
+mod lit;
 mod module;
+mod naming;
+mod parse;
+mod pat;
 mod scope;
+#[cfg(feature = "serde")]
+mod ser;
+mod signature;
+mod static_gen;
+mod stmt;
 mod struct_gen;
 mod trait_gen;
+mod type_alias;
 mod type_def;
+mod union_gen;
+mod validate;
 mod variant;
+mod visit;
 mod vis;
 
 
 
+pub use arg::{Arg, Receiver};
 pub use associated_type::*;
 pub use attributes::*;
 pub use block::*;
-pub use bounds::Bound;
+pub use bounds::{Bound, Bounds, WherePredicate};
+pub use const_gen::*;
+pub use control_flow::{Arm, Cond, If, Loop, Match};
+pub use docs::{Docs, ModuleDocs};
 pub use enum_gen::*;
+pub use error::CodegenError;
+pub use expr::{BinOp, Delimiter, Expr, UnOp};
+pub use extern_block::*;
 pub use field::*;
+pub use fields::Fields;
+pub use ident::{Edition, Ident, IdentError, is_keyword};
 pub use formatter::*;
 pub use function::*;
+pub use generics::GenericParam;
 pub use impl_gen::*;
 pub use import::*;
+pub use item::Item;
+pub use lit::{Lit, Radix};
 pub use module::*;
+pub use naming::{Case, NameAllocator, to_screaming_snake_case, to_snake_case, to_upper_camel_case};
+pub use parse::TypeParseError;
+pub use pat::Pat;
 pub use scope::*;
+#[cfg(feature = "serde")]
+pub use ser::{MapStyle, SeqStyle, SerializeError, Serializer, to_expr};
+pub use signature::Signature;
+pub use static_gen::*;
+pub use stmt::Stmt;
 pub use struct_gen::*;
 pub use trait_gen::*;
-pub use type_def::Type;
+pub use type_alias::*;
+pub use type_def::{FnPtr, GenericArg, PathSegment, Type, TypeBound, TypePath};
+pub use union_gen::*;
+pub use validate::{Diagnostic, Severity};
 pub use variant::*;
 pub use vis::*;
 
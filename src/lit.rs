@@ -0,0 +1,353 @@
+use std::ffi::{CStr, CString};
+use std::fmt::{self, Write};
+
+use crate::formatter::Formatter;
+
+
+/// A literal, like `"foo"`, `b'x'` or `0xffu8`, that is escaped as needed
+/// when formatted.
+///
+/// ```
+/// use codegen::{Lit, Radix};
+///
+/// assert_eq!(Lit::from("say \"hi\"\n").to_string(), r#""say \"hi\"\n""#);
+/// assert_eq!(Lit::raw_str("a \"# b").to_string(), r###"r##"a "# b"##"###);
+/// assert_eq!(Lit::from(255u8).radix(Radix::Hex).suffixed().to_string(), "0xffu8");
+/// assert_eq!(Lit::from(1f64).to_string(), "1.0");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lit {
+    kind: LitKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LitKind {
+    Str(String),
+    RawStr(String),
+    ByteStr(Vec<u8>),
+    CStr(CString),
+    Byte(u8),
+    Char(char),
+    Bool(bool),
+    Int {
+        negative: bool,
+        value: u128,
+        ty: &'static str,
+        radix: Radix,
+        suffix: bool,
+    },
+    Float {
+        repr: String,
+        ty: &'static str,
+        suffix: bool,
+    },
+}
+
+/// The radix an integer literal is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Radix {
+    /// `0b1010`
+    Binary,
+    /// `0o12`
+    Octal,
+    /// `10`
+    #[default]
+    Decimal,
+    /// `0xa`
+    Hex,
+}
+
+impl Lit {
+    /// Returns a string literal, like `"foo"`.
+    pub fn str<S>(value: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Lit { kind: LitKind::Str(value.into()) }
+    }
+
+    /// Returns a raw string literal, like `r#"foo"#`, with as many `#` as
+    /// needed to hold `value`.
+    pub fn raw_str<S>(value: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Lit { kind: LitKind::RawStr(value.into()) }
+    }
+
+    /// Returns a byte string literal, like `b"foo"`.
+    pub fn byte_str<B>(value: B) -> Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        Lit { kind: LitKind::ByteStr(value.into()) }
+    }
+
+    /// Returns a C string literal, like `c"foo"`.
+    pub fn c_str(value: &CStr) -> Self {
+        Lit { kind: LitKind::CStr(value.to_owned()) }
+    }
+
+    /// Returns a byte literal, like `b'x'`.
+    pub fn byte(value: u8) -> Self {
+        Lit { kind: LitKind::Byte(value) }
+    }
+
+    /// Returns a character literal, like `'x'`.
+    pub fn char(value: char) -> Self {
+        Lit { kind: LitKind::Char(value) }
+    }
+
+    /// Returns a boolean literal.
+    pub fn bool(value: bool) -> Self {
+        Lit { kind: LitKind::Bool(value) }
+    }
+
+    /// Write an integer literal in the given radix. Has no effect on other
+    /// literals.
+    pub fn radix(mut self, radix: Radix) -> Self {
+        if let LitKind::Int { radix: ref mut r, .. } = self.kind {
+            *r = radix;
+        }
+        self
+    }
+
+    /// Write a numeric literal with the suffix of the type it was made
+    /// from, like `1u8` or `1.0f32`. Has no effect on other literals.
+    pub fn suffixed(mut self) -> Self {
+        match self.kind {
+            LitKind::Int { ref mut suffix, .. } | LitKind::Float { ref mut suffix, .. } => {
+                *suffix = true;
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Whether or not the literal starts with a `-`, making it a negation
+    /// rather than a plain literal.
+    pub(crate) fn is_negative(&self) -> bool {
+        match &self.kind {
+            LitKind::Int { negative, .. } => *negative,
+            LitKind::Float { repr, .. } => repr.starts_with('-'),
+            _ => false,
+        }
+    }
+
+    /// Formats the literal using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            LitKind::Str(value) => {
+                write!(fmt, "\"")?;
+                for c in value.chars() {
+                    fmt_char(c, '"', fmt)?;
+                }
+                write!(fmt, "\"")
+            }
+            LitKind::RawStr(value) => {
+                let hashes = "#".repeat(raw_str_hashes(value));
+
+                // Lines after the first must not be indented, as that would
+                // change the value
+                write!(fmt, "r{}\"", hashes)?;
+                fmt.write_unindented(value);
+                write!(fmt, "\"{}", hashes)
+            }
+            LitKind::ByteStr(value) => {
+                write!(fmt, "b\"")?;
+                for &b in value {
+                    fmt_byte(b, b'"', fmt)?;
+                }
+                write!(fmt, "\"")
+            }
+            LitKind::CStr(value) => {
+                write!(fmt, "c\"")?;
+                for &b in value.to_bytes() {
+                    fmt_byte(b, b'"', fmt)?;
+                }
+                write!(fmt, "\"")
+            }
+            LitKind::Byte(value) => {
+                write!(fmt, "b'")?;
+                fmt_byte(*value, b'\'', fmt)?;
+                write!(fmt, "'")
+            }
+            LitKind::Char(value) => {
+                write!(fmt, "'")?;
+                fmt_char(*value, '\'', fmt)?;
+                write!(fmt, "'")
+            }
+            LitKind::Bool(value) => write!(fmt, "{}", value),
+            LitKind::Int { negative, value, ty, radix, suffix } => {
+                if *negative {
+                    write!(fmt, "-")?;
+                }
+
+                match radix {
+                    Radix::Binary => write!(fmt, "{:#b}", value)?,
+                    Radix::Octal => write!(fmt, "{:#o}", value)?,
+                    Radix::Decimal => write!(fmt, "{}", value)?,
+                    Radix::Hex => write!(fmt, "{:#x}", value)?,
+                }
+
+                if *suffix {
+                    write!(fmt, "{}", ty)?;
+                }
+
+                Ok(())
+            }
+            LitKind::Float { repr, ty, suffix } => {
+                write!(fmt, "{}", repr)?;
+
+                // `f64::NAN` and friends are paths, not literals
+                if *suffix && repr.starts_with(|c: char| c == '-' || c.is_ascii_digit()) {
+                    write!(fmt, "{}", ty)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Writes `c` as it appears within a string or character literal delimited
+/// by `quote`.
+fn fmt_char(c: char, quote: char, fmt: &mut Formatter<'_>) -> fmt::Result {
+    match c {
+        '\\' => write!(fmt, "\\\\"),
+        '\n' => write!(fmt, "\\n"),
+        '\r' => write!(fmt, "\\r"),
+        '\t' => write!(fmt, "\\t"),
+        '\0' => write!(fmt, "\\0"),
+        c if c == quote => write!(fmt, "\\{}", c),
+        c if c.is_control() => write!(fmt, "\\u{{{:x}}}", c as u32),
+        c => write!(fmt, "{}", c),
+    }
+}
+
+/// Writes `b` as it appears within a byte or byte string literal delimited
+/// by `quote`.
+fn fmt_byte(b: u8, quote: u8, fmt: &mut Formatter<'_>) -> fmt::Result {
+    match b {
+        b'\\' => write!(fmt, "\\\\"),
+        b'\n' => write!(fmt, "\\n"),
+        b'\r' => write!(fmt, "\\r"),
+        b'\t' => write!(fmt, "\\t"),
+        b'\0' => write!(fmt, "\\0"),
+        b if b == quote => write!(fmt, "\\{}", b as char),
+        b' '..=b'~' => write!(fmt, "{}", b as char),
+        b => write!(fmt, "\\x{:02x}", b),
+    }
+}
+
+/// Returns the number of `#` needed so that `value` doesn't end a raw string
+/// early, i.e. one more than the longest run of `#` after a `"`.
+fn raw_str_hashes(value: &str) -> usize {
+    value.match_indices('"')
+        .map(|(i, _)| value[i + 1..].chars().take_while(|&c| c == '#').count() + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+macro_rules! impl_from_int {
+    ($($ty:ident => |$value:ident| $parts:expr,)*) => {
+        $(
+            impl From<$ty> for Lit {
+                fn from($value: $ty) -> Self {
+                    let (negative, value) = $parts;
+
+                    Lit {
+                        kind: LitKind::Int {
+                            negative,
+                            value,
+                            ty: stringify!($ty),
+                            radix: Radix::Decimal,
+                            suffix: false,
+                        },
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int! {
+    i8 => |value| (value < 0, value.unsigned_abs() as u128),
+    i16 => |value| (value < 0, value.unsigned_abs() as u128),
+    i32 => |value| (value < 0, value.unsigned_abs() as u128),
+    i64 => |value| (value < 0, value.unsigned_abs() as u128),
+    i128 => |value| (value < 0, value.unsigned_abs()),
+    isize => |value| (value < 0, value.unsigned_abs() as u128),
+    u8 => |value| (false, value as u128),
+    u16 => |value| (false, value as u128),
+    u32 => |value| (false, value as u128),
+    u64 => |value| (false, value as u128),
+    u128 => |value| (false, value),
+    usize => |value| (false, value as u128),
+}
+
+macro_rules! impl_from_float {
+    ($($ty:ident)*) => {
+        $(
+            impl From<$ty> for Lit {
+                fn from(value: $ty) -> Self {
+                    let repr = if value.is_nan() {
+                        concat!(stringify!($ty), "::NAN").to_owned()
+                    } else if value.is_infinite() && value > 0.0 {
+                        concat!(stringify!($ty), "::INFINITY").to_owned()
+                    } else if value.is_infinite() {
+                        concat!(stringify!($ty), "::NEG_INFINITY").to_owned()
+                    } else {
+                        // `Debug` always writes a `.` or an exponent, so the
+                        // literal stays a float
+                        format!("{:?}", value)
+                    };
+
+                    Lit {
+                        kind: LitKind::Float { repr, ty: stringify!($ty), suffix: false },
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_float!(f32 f64);
+
+impl From<bool> for Lit {
+    fn from(value: bool) -> Self {
+        Lit::bool(value)
+    }
+}
+
+impl From<char> for Lit {
+    fn from(value: char) -> Self {
+        Lit::char(value)
+    }
+}
+
+impl From<&str> for Lit {
+    fn from(value: &str) -> Self {
+        Lit::str(value)
+    }
+}
+
+impl From<String> for Lit {
+    fn from(value: String) -> Self {
+        Lit::str(value)
+    }
+}
+
+impl From<&CStr> for Lit {
+    fn from(value: &CStr) -> Self {
+        Lit::c_str(value)
+    }
+}
+
+impl fmt::Display for Lit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        Lit::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
@@ -2,16 +2,26 @@ use std::fmt::{self, Write};
 
 use crate::attributes::Attributes;
 use crate::docs::ModuleDocs;
+use crate::error::CodegenError;
 use crate::formatter::Formatter;
+use crate::validate::{Diagnostic, check_name};
+use crate::visit::{Text, Visit};
 use crate::function::Function;
+use crate::import::{ExternCrate, ImportOrder, UseTree};
 use crate::scope::Scope;
 
 use crate::vis::Vis;
 
+use crate::const_gen::Const;
 use crate::enum_gen::Enum;
+use crate::extern_block::ExternBlock;
 use crate::impl_gen::Impl;
+use crate::static_gen::Static;
 use crate::struct_gen::Struct;
 use crate::trait_gen::Trait;
+use crate::type_alias::TypeAlias;
+use crate::union_gen::Union;
+use crate::type_def::Type;
 
 
 use crate::impl_macros::{
@@ -22,7 +32,7 @@ use crate::impl_macros::{
 
 
 /// Defines a module.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Module {
     /// Module name
     name: String,
@@ -74,6 +84,45 @@ impl Module {
         self
     }
 
+    /// Import a `use` tree into the module's scope, such as
+    /// `std::io::{self, Read}`.
+    pub fn import_tree<T>(&mut self, tree: T) -> &mut Self
+    where
+        T: Into<UseTree>,
+    {
+        self.scope.import_tree(tree);
+        self
+    }
+
+    /// Shortens the fully-qualified type paths used by the module's items and
+    /// imports them. See [`Scope::collect_imports`].
+    pub fn collect_imports(&mut self) -> &mut Self {
+        self.scope.collect_imports();
+        self
+    }
+
+    /// Removes the imports that nothing in the module refers to. See
+    /// [`Scope::prune_imports`].
+    pub fn prune_imports(&mut self, scan_bodies: bool) -> &mut Self {
+        self.scope.prune_imports(scan_bodies);
+        self
+    }
+
+    /// Set the order in which the module's imports are formatted.
+    pub fn set_import_order(&mut self, order: ImportOrder) -> &mut Self {
+        self.scope.set_import_order(order);
+        self
+    }
+
+    /// Push a new `extern crate` declaration into the module's scope,
+    /// returning a mutable reference to it.
+    pub fn extern_crate<S>(&mut self, name: S) -> &mut ExternCrate
+    where
+        S: AsRef<str>,
+    {
+        self.scope.extern_crate(name)
+    }
+
     /// Push a new module definition, returning a mutable reference to it.
     ///
     /// # Panics
@@ -132,6 +181,25 @@ impl Module {
         self
     }
 
+    /// Push a new module definition, returning a mutable reference to it, or
+    /// an error if a module with the same name is already defined.
+    pub fn try_new_module<S>(&mut self, name: S) -> Result<&mut Module, CodegenError>
+    where
+        S: AsRef<str>,
+    {
+        let module_name = &self.name;
+        self.scope.try_new_module(name).map_err(|err| err.in_module(module_name))
+    }
+
+    /// Push a module definition, or return an error if a module with the same
+    /// name is already defined.
+    pub fn try_push_module(&mut self, item: Module) -> Result<&mut Self, CodegenError> {
+        match self.scope.try_push_module(item) {
+            Ok(_) => Ok(self),
+            Err(err) => Err(err.in_module(&self.name)),
+        }
+    }
+
     /// Push a new struct definition, returning a mutable reference to it.
     pub fn new_struct(&mut self, name: &str) -> &mut Struct {
         self.scope.new_struct(name)
@@ -143,6 +211,20 @@ impl Module {
         self
     }
 
+    /// Push a new union definition, returning a mutable reference to it.
+    pub fn new_union<S>(&mut self, name: S) -> &mut Union
+    where
+        S: AsRef<str>,
+    {
+        self.scope.new_union(name)
+    }
+
+    /// Push a union definition
+    pub fn push_union(&mut self, item: Union) -> &mut Self {
+        self.scope.push_union(item);
+        self
+    }
+
     /// Push a new function definition, returning a mutable reference to it.
     pub fn new_fn(&mut self, name: &str) -> &mut Function {
         self.scope.new_fn(name)
@@ -182,6 +264,77 @@ impl Module {
         self
     }
 
+    /// Push a new `const` definition, returning a mutable reference to it.
+    pub fn new_const<S, T>(&mut self, name: S, ty: T) -> &mut Const
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.scope.new_const(name, ty)
+    }
+
+    /// Push a `const` definition.
+    pub fn push_const(&mut self, item: Const) -> &mut Self {
+        self.scope.push_const(item);
+        self
+    }
+
+    /// Push a new `static` definition, returning a mutable reference to it.
+    pub fn new_static<S, T>(&mut self, name: S, ty: T) -> &mut Static
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.scope.new_static(name, ty)
+    }
+
+    /// Push a `static` definition.
+    pub fn push_static(&mut self, item: Static) -> &mut Self {
+        self.scope.push_static(item);
+        self
+    }
+
+    /// Push a new type alias, returning a mutable reference to it.
+    pub fn new_type_alias<S, T>(&mut self, name: S, target: T) -> &mut TypeAlias
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.scope.new_type_alias(name, target)
+    }
+
+    /// Push a type alias.
+    pub fn push_type_alias(&mut self, item: TypeAlias) -> &mut Self {
+        self.scope.push_type_alias(item);
+        self
+    }
+
+    /// Push a new `extern` block with the given ABI, returning a mutable
+    /// reference to it.
+    pub fn new_extern_block<S>(&mut self, abi: S) -> &mut ExternBlock
+    where
+        S: AsRef<str>,
+    {
+        self.scope.new_extern_block(abi)
+    }
+
+    /// Push an `extern` block.
+    pub fn push_extern_block(&mut self, item: ExternBlock) -> &mut Self {
+        self.scope.push_extern_block(item);
+        self
+    }
+
+    /// Reports problems with the module's name and everything defined in it.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        check_name(&self.name, path, diags);
+        self.scope.validate_in(path, diags);
+    }
+
+    /// Checks that the module can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        self.scope.check().map_err(|err| err.in_module(&self.name))
+    }
+
     /// Formats the module using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.attrs.fmt_attrs(fmt)?;
@@ -194,3 +347,15 @@ impl Module {
     impl_doc_methods!(docs);
     impl_vis_methods!(field => vis);
 }
+
+impl Visit for Module {
+    // The module's items belong to its own scope, so only its attributes are
+    // visited.
+    fn visit_types(&self, _: &mut dyn FnMut(&Type)) {}
+
+    fn visit_types_mut(&mut self, _: &mut dyn FnMut(&mut Type)) {}
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+
+use crate::enum_gen::Enum;
+use crate::ident::{Edition, Ident};
+use crate::lit::Lit;
+use crate::scope::Scope;
+use crate::struct_gen::Struct;
+
+
+/// A naming convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// Modules, fns, fields and locals, e.g. `foo_bar`.
+    Snake,
+    /// Types, traits and variants, e.g. `FooBar`.
+    UpperCamel,
+    /// Consts and statics, e.g. `FOO_BAR`.
+    ScreamingSnake,
+}
+
+impl Case {
+    /// Converts `name` to this naming convention.
+    pub fn convert<S>(&self, name: S) -> String
+    where
+        S: AsRef<str>
+    {
+        match self {
+            Case::Snake => to_snake_case(name),
+            Case::UpperCamel => to_upper_camel_case(name),
+            Case::ScreamingSnake => to_screaming_snake_case(name),
+        }
+    }
+
+    /// Appends a number to `name` to make it unique, e.g. `foo_2` or `Foo2`.
+    fn suffixed(&self, name: &str, n: usize) -> String {
+        match self {
+            Case::UpperCamel => format!("{}{}", name, n),
+            Case::Snake | Case::ScreamingSnake => format!("{}_{}", name, n),
+        }
+    }
+}
+
+/// Converts `name` to `snake_case`.
+///
+/// Words are split on non-alphanumeric characters and case changes, with
+/// acronyms kept together, so `HTTPServer2Config` becomes
+/// `http_server2_config`. Leading underscores are kept.
+pub fn to_snake_case<S>(name: S) -> String
+where
+    S: AsRef<str>
+{
+    let (prefix, words) = split_words(name.as_ref());
+
+    let words: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+    format!("{}{}", prefix, words.join("_"))
+}
+
+/// Converts `name` to `UpperCamelCase`.
+///
+/// Acronyms are only capitalized on their first letter, so `http_server`
+/// and `HTTPServer` both become `HttpServer`. Leading underscores are kept.
+pub fn to_upper_camel_case<S>(name: S) -> String
+where
+    S: AsRef<str>
+{
+    let (prefix, words) = split_words(name.as_ref());
+    let mut camel = prefix.to_owned();
+
+    for word in words {
+        let mut chars = word.chars();
+
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(&chars.as_str().to_lowercase());
+        }
+    }
+
+    camel
+}
+
+/// Converts `name` to `SCREAMING_SNAKE_CASE`. Leading underscores are kept.
+pub fn to_screaming_snake_case<S>(name: S) -> String
+where
+    S: AsRef<str>
+{
+    let (prefix, words) = split_words(name.as_ref());
+
+    let words: Vec<String> = words.iter().map(|word| word.to_uppercase()).collect();
+    format!("{}{}", prefix, words.join("_"))
+}
+
+/// Splits `name` into its leading underscores and its words.
+fn split_words(name: &str) -> (&str, Vec<&str>) {
+    let rest = name.trim_start_matches('_');
+    let prefix = &name[..name.len() - rest.len()];
+
+    let mut words = vec![];
+
+    for part in rest.split(|c: char| !c.is_alphanumeric()).filter(|part| !part.is_empty()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut start = 0;
+
+        for (i, &(idx, c)) in chars.iter().enumerate().skip(1) {
+            let prev = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|&(_, c)| c);
+
+            // `fooBar` and `foo2Bar`, or the `S` of `HTTPServer`
+            let boundary = c.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next.is_some_and(char::is_lowercase)));
+
+            if boundary {
+                words.push(&part[start..idx]);
+                start = idx;
+            }
+        }
+
+        words.push(&part[start..]);
+    }
+
+    (prefix, words)
+}
+
+
+/// Hands out unique identifiers for names from an external source, like a
+/// schema, converted to a naming convention.
+///
+/// When two names map to the same identifier, a number is appended to the
+/// later one (`foo_bar`, `foo_bar_2`). The mapping from original to
+/// generated names is kept, so that serde `rename` attributes can be emitted.
+#[derive(Debug, Clone)]
+pub struct NameAllocator {
+    case: Case,
+    edition: Edition,
+    reserved: HashSet<String>,
+    names: IndexMap<String, String>,
+}
+
+impl NameAllocator {
+    /// Returns a new allocator that converts names to the given case.
+    pub fn new(case: Case) -> Self {
+        NameAllocator {
+            case,
+            edition: Edition::default(),
+            reserved: HashSet::new(),
+            names: IndexMap::new(),
+        }
+    }
+
+    /// Returns a new allocator that won't hand out the names of the items
+    /// already defined in `scope`.
+    pub fn from_scope(scope: &Scope, case: Case) -> Self {
+        let mut allocator = Self::new(case);
+        allocator.reserved.extend(scope.item_names().map(str::to_owned));
+        allocator
+    }
+
+    /// Returns a new allocator that won't hand out the names of the fields
+    /// already defined in `item`.
+    pub fn from_struct(item: &Struct, case: Case) -> Self {
+        let mut allocator = Self::new(case);
+        allocator.reserved.extend(item.field_names().map(str::to_owned));
+        allocator
+    }
+
+    /// Returns a new allocator that won't hand out the names of the variants
+    /// already defined in `item`.
+    pub fn from_enum(item: &Enum, case: Case) -> Self {
+        let mut allocator = Self::new(case);
+        allocator.reserved.extend(item.variant_names().map(str::to_owned));
+        allocator
+    }
+
+    /// Set the edition whose keywords are escaped in generated names.
+    pub fn edition(&mut self, edition: Edition) -> &mut Self {
+        self.edition = edition;
+        self
+    }
+
+    /// Reserve a name, so that it is never handed out.
+    pub fn reserve<S>(&mut self, name: S) -> &mut Self
+    where
+        S: AsRef<str>
+    {
+        self.reserved.insert(name.as_ref().to_owned());
+        self
+    }
+
+    /// Returns a unique identifier for `original`.
+    ///
+    /// Allocating the same original name twice returns the same identifier.
+    pub fn allocate<S>(&mut self, original: S) -> String
+    where
+        S: AsRef<str>
+    {
+        let original = original.as_ref();
+
+        if let Some(name) = self.names.get(original) {
+            return name.clone();
+        }
+
+        let base = Ident::sanitize_with_edition(self.case.convert(original), self.edition);
+        let mut name = base.to_string();
+        let mut n = 2;
+
+        while self.reserved.contains(&name) {
+            let suffixed = self.case.suffixed(base.unraw(), n);
+            name = Ident::sanitize_with_edition(suffixed, self.edition).into();
+            n += 1;
+        }
+
+        self.reserved.insert(name.clone());
+        self.names.insert(original.to_owned(), name.clone());
+        name
+    }
+
+    /// Returns the identifier allocated for `original`, if any.
+    pub fn get<S>(&self, original: S) -> Option<&str>
+    where
+        S: AsRef<str>
+    {
+        self.names.get(original.as_ref()).map(String::as_str)
+    }
+
+    /// Returns the original name that `generated` was allocated for, if any.
+    pub fn original<S>(&self, generated: S) -> Option<&str>
+    where
+        S: AsRef<str>
+    {
+        let generated = generated.as_ref();
+
+        self.names.iter()
+            .find(|(_, name)| *name == generated)
+            .map(|(original, _)| original.as_str())
+    }
+
+    /// Returns the pairs of original and generated names, in the order they
+    /// were allocated.
+    pub fn mapping(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.names.iter().map(|(original, name)| (original.as_str(), name.as_str()))
+    }
+
+    /// Returns a `serde(rename = "...")` attribute for `generated`, if it was
+    /// allocated for an original name that differs from it.
+    pub fn serde_rename<S>(&self, generated: S) -> Option<String>
+    where
+        S: AsRef<str>
+    {
+        let generated = generated.as_ref();
+        let original = self.original(generated)?;
+
+        (original != generated.strip_prefix("r#").unwrap_or(generated))
+            .then(|| format!("serde(rename = {})", Lit::str(original)))
+    }
+}
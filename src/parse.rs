@@ -0,0 +1,731 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::bounds::WherePredicate;
+use crate::generics::GenericParam;
+use crate::import::UseTree;
+use crate::type_def::{FnPtr, GenericArg, PathSegment, Type, TypeBound, TypePath};
+
+
+/// An error returned when a string can't be parsed into a [`Type`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParseError {
+    src: String,
+    offset: usize,
+    msg: String,
+}
+
+impl TypeParseError {
+    /// Returns the string that failed to parse.
+    pub fn src(&self) -> &str {
+        self.src.as_str()
+    }
+
+    /// Returns the byte offset into the string where parsing failed.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns a description of what went wrong.
+    pub fn message(&self) -> &str {
+        self.msg.as_str()
+    }
+}
+
+impl fmt::Display for TypeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {} in `{}`", self.msg, self.offset, self.src)
+    }
+}
+
+impl Error for TypeParseError {}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Lifetime(&'a str),
+    Literal(&'a str),
+    Punct(&'static str),
+}
+
+impl<'a> fmt::Display for Token<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(s) | Token::Lifetime(s) | Token::Literal(s) => write!(f, "`{}`", s),
+            Token::Punct(s) => write!(f, "`{}`", s),
+        }
+    }
+}
+
+/// Punctuation, longest first so that e.g. `::` wins over `:`.
+const PUNCT: &[&str] = &[
+    "...", "::", "->", "<", ">", ",", "&", "*", "(", ")", "[", "]", "{", "}", ";", ":", "=",
+    "+", "!", "?", "-",
+];
+
+
+/// A small recursive descent parser over the subset of Rust syntax that
+/// describes types.
+pub(crate) struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<(Token<'a>, usize)>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Tokenizes the given source.
+    pub(crate) fn new(src: &'a str) -> Result<Self, TypeParseError> {
+        let mut tokens = vec![];
+        let mut rest = src;
+
+        loop {
+            let trimmed = rest.trim_start();
+            let offset = src.len() - trimmed.len();
+            rest = trimmed;
+
+            let Some(c) = rest.chars().next() else {
+                break;
+            };
+
+            let len = if let Some(len) = char_len(rest) {
+                tokens.push((Token::Literal(&rest[..len]), offset));
+                len
+            } else if c == '\'' {
+                let len = 1 + ident_len(&rest[1..]);
+                if len == 1 {
+                    return Err(error(src, offset, "expected a lifetime name"));
+                }
+                tokens.push((Token::Lifetime(&rest[..len]), offset));
+                len
+            } else if rest.starts_with("r#") || c == '_' || c.is_alphabetic() {
+                let prefix = if rest.starts_with("r#") { 2 } else { 0 };
+                let len = prefix + ident_len(&rest[prefix..]);
+                tokens.push((Token::Ident(&rest[..len]), offset));
+                len
+            } else if c.is_ascii_digit() {
+                let len = ident_len(rest);
+                tokens.push((Token::Literal(&rest[..len]), offset));
+                len
+            } else if c == '"' {
+                let len = match str_len(rest) {
+                    Some(len) => len,
+                    None => return Err(error(src, offset, "unterminated string")),
+                };
+                tokens.push((Token::Literal(&rest[..len]), offset));
+                len
+            } else if let Some(punct) = PUNCT.iter().find(|p| rest.starts_with(**p)) {
+                tokens.push((Token::Punct(punct), offset));
+                punct.len()
+            } else {
+                return Err(error(src, offset, format!("unexpected character `{}`", c)));
+            };
+
+            rest = &rest[len..];
+        }
+
+        Ok(Parser { src, tokens, pos: 0 })
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).map(|(token, _)| *token)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<Token<'a>> {
+        self.tokens.get(self.pos + n).map(|(token, _)| *token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos)
+            .map(|(_, offset)| *offset)
+            .unwrap_or(self.src.len())
+    }
+
+    fn is_punct(&self, punct: &'static str) -> bool {
+        self.peek() == Some(Token::Punct(punct))
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.peek() == Some(Token::Ident(keyword))
+    }
+
+    fn eat_punct(&mut self, punct: &'static str) -> bool {
+        let found = self.is_punct(punct);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    pub(crate) fn error<S>(&self, msg: S) -> TypeParseError
+    where
+        S: Into<String>,
+    {
+        error(self.src, self.offset(), msg)
+    }
+
+    fn unexpected(&self, expected: &str) -> TypeParseError {
+        match self.peek() {
+            Some(token) => self.error(format!("expected {}, found {}", expected, token)),
+            None => self.error(format!("expected {}, found end of input", expected)),
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &'static str) -> Result<(), TypeParseError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", punct)))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<&'a str, TypeParseError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    fn expect_lifetime(&mut self) -> Result<&'a str, TypeParseError> {
+        match self.peek() {
+            Some(Token::Lifetime(lifetime)) => {
+                self.pos += 1;
+                Ok(lifetime)
+            }
+            _ => Err(self.unexpected("a lifetime")),
+        }
+    }
+
+    /// Returns an error unless all input has been consumed.
+    pub(crate) fn finish(&self) -> Result<(), TypeParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(self.error(format!("unexpected {}", token))),
+        }
+    }
+
+    /// Collects tokens verbatim up to (but not including) the given closing
+    /// punctuation at the current nesting depth.
+    fn verbatim_until(&mut self, closing: &[&str]) -> Result<&'a str, TypeParseError> {
+        let start = self.offset();
+        let mut depth = 0usize;
+
+        loop {
+            match self.peek() {
+                None if depth == 0 => break,
+                None => return Err(self.unexpected("a closing delimiter")),
+                Some(Token::Punct(p)) if depth == 0 && closing.contains(&p) => break,
+                Some(Token::Punct("(" | "[" | "{")) => depth += 1,
+                Some(Token::Punct(")" | "]" | "}")) => depth = depth.saturating_sub(1),
+                Some(_) => {}
+            }
+
+            self.pos += 1;
+        }
+
+        let text = self.src[start..self.offset()].trim();
+
+        if text.is_empty() {
+            return Err(self.unexpected("an expression"));
+        }
+
+        Ok(text)
+    }
+
+    /// Parses a single type.
+    pub(crate) fn parse_type(&mut self) -> Result<Type, TypeParseError> {
+        match self.peek() {
+            Some(Token::Punct("!")) => {
+                self.pos += 1;
+                Ok(Type::Never)
+            }
+            Some(Token::Ident("_")) => {
+                self.pos += 1;
+                Ok(Type::Infer)
+            }
+            Some(Token::Punct("(")) => self.parse_tuple(),
+            Some(Token::Punct("[")) => self.parse_slice_or_array(),
+            Some(Token::Punct("&")) => {
+                self.pos += 1;
+
+                let lifetime = match self.peek() {
+                    Some(Token::Lifetime(lifetime)) => {
+                        self.pos += 1;
+                        Some(lifetime.to_owned())
+                    }
+                    _ => None,
+                };
+
+                let mutable = self.eat_keyword("mut");
+                let inner = Box::new(self.parse_type()?);
+
+                Ok(Type::Ref { lifetime, mutable, inner })
+            }
+            Some(Token::Punct("*")) => {
+                self.pos += 1;
+
+                let mutable = if self.eat_keyword("mut") {
+                    true
+                } else if self.eat_keyword("const") {
+                    false
+                } else {
+                    return Err(self.unexpected("`const` or `mut`"));
+                };
+
+                let inner = Box::new(self.parse_type()?);
+
+                Ok(Type::Ptr { mutable, inner })
+            }
+            Some(Token::Punct("<")) => self.parse_qself(),
+            Some(Token::Ident("dyn")) => {
+                self.pos += 1;
+                Ok(Type::TraitObject(self.parse_bounds()?))
+            }
+            Some(Token::Ident("impl")) => {
+                self.pos += 1;
+                Ok(Type::ImplTrait(self.parse_bounds()?))
+            }
+            Some(Token::Ident("fn" | "unsafe" | "extern")) => self.parse_fn_ptr(),
+            Some(Token::Ident(_)) | Some(Token::Punct("::")) => {
+                Ok(Type::Path(self.parse_path()?))
+            }
+            _ => Err(self.unexpected("a type")),
+        }
+    }
+
+    fn parse_tuple(&mut self) -> Result<Type, TypeParseError> {
+        let (mut elems, trailing_comma) = self.parse_paren_types()?;
+
+        // `(T)` is just a parenthesized `T`, not a tuple.
+        if elems.len() == 1 && !trailing_comma {
+            return Ok(elems.pop().unwrap());
+        }
+
+        Ok(Type::Tuple(elems))
+    }
+
+    /// Parses a parenthesized, comma separated list of types. Also returns
+    /// whether or not the list ended with a trailing comma.
+    fn parse_paren_types(&mut self) -> Result<(Vec<Type>, bool), TypeParseError> {
+        self.expect_punct("(")?;
+
+        let mut elems = vec![];
+        let mut trailing_comma = false;
+
+        while !self.is_punct(")") {
+            elems.push(self.parse_type()?);
+            trailing_comma = self.eat_punct(",");
+
+            if !trailing_comma {
+                break;
+            }
+        }
+
+        self.expect_punct(")")?;
+        Ok((elems, trailing_comma))
+    }
+
+    fn parse_slice_or_array(&mut self) -> Result<Type, TypeParseError> {
+        self.expect_punct("[")?;
+
+        let inner = Box::new(self.parse_type()?);
+
+        let ty = if self.eat_punct(";") {
+            let len = self.verbatim_until(&["]"])?;
+            Type::Array(inner, len.to_owned())
+        } else {
+            Type::Slice(inner)
+        };
+
+        self.expect_punct("]")?;
+        Ok(ty)
+    }
+
+    fn parse_qself(&mut self) -> Result<Type, TypeParseError> {
+        self.expect_punct("<")?;
+
+        let self_ty = Box::new(self.parse_type()?);
+
+        let trait_ = if self.eat_keyword("as") {
+            Some(self.parse_path()?)
+        } else {
+            None
+        };
+
+        self.expect_punct(">")?;
+
+        let mut path = vec![];
+
+        while self.eat_punct("::") {
+            path.push(self.parse_segment()?);
+        }
+
+        if path.is_empty() {
+            return Err(self.unexpected("`::`"));
+        }
+
+        Ok(Type::QSelf { self_ty, trait_, path })
+    }
+
+    fn parse_fn_ptr(&mut self) -> Result<Type, TypeParseError> {
+        let mut fn_ptr = FnPtr::new();
+
+        if self.eat_keyword("unsafe") {
+            fn_ptr.set_unsafe(true);
+        }
+
+        if self.eat_keyword("extern") {
+            match self.peek() {
+                Some(Token::Literal(abi)) if abi.starts_with('"') => {
+                    self.pos += 1;
+                    fn_ptr.extern_abi(abi.trim_matches('"'));
+                }
+                _ => {
+                    fn_ptr.extern_abi("C");
+                }
+            }
+        }
+
+        if !self.eat_keyword("fn") {
+            return Err(self.unexpected("`fn`"));
+        }
+
+        self.expect_punct("(")?;
+
+        while !self.is_punct(")") {
+            if self.eat_punct("...") {
+                fn_ptr.set_variadic(true);
+                break;
+            }
+
+            // Skip over argument names, e.g. `fn(len: usize)`.
+            if matches!(self.peek(), Some(Token::Ident(_)))
+                && self.peek_nth(1) == Some(Token::Punct(":"))
+            {
+                self.pos += 2;
+            }
+
+            fn_ptr.arg(self.parse_type()?);
+
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+
+        self.expect_punct(")")?;
+
+        if self.eat_punct("->") {
+            fn_ptr.ret(self.parse_type()?);
+        }
+
+        Ok(Type::FnPtr(fn_ptr))
+    }
+
+    /// Parses a path, such as `std::collections::HashMap<K, V>`.
+    pub(crate) fn parse_path(&mut self) -> Result<TypePath, TypeParseError> {
+        let global = self.eat_punct("::");
+        let mut segments = vec![self.parse_segment()?];
+
+        while self.is_punct("::") && self.peek_nth(1) != Some(Token::Punct("<")) {
+            self.pos += 1;
+            segments.push(self.parse_segment()?);
+        }
+
+        let mut path = TypePath::new(segments);
+        path.set_global(global);
+        Ok(path)
+    }
+
+    fn parse_segment(&mut self) -> Result<PathSegment, TypeParseError> {
+        let mut segment = PathSegment::new(self.expect_ident()?);
+
+        // Turbofish is allowed, but not required, in type position.
+        if self.is_punct("::") && self.peek_nth(1) == Some(Token::Punct("<")) {
+            self.pos += 1;
+        }
+
+        if self.eat_punct("<") {
+            while !self.is_punct(">") {
+                segment.push_arg(self.parse_generic_arg()?);
+
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+
+            self.expect_punct(">")?;
+        } else if self.is_punct("(") {
+            // Parenthesized arguments, e.g. `Fn(u8) -> u8`.
+            let (inputs, _) = self.parse_paren_types()?;
+
+            let output = if self.eat_punct("->") {
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+
+            segment.set_fn_sugar(inputs, output);
+        }
+
+        Ok(segment)
+    }
+
+    fn parse_generic_arg(&mut self) -> Result<GenericArg, TypeParseError> {
+        match (self.peek(), self.peek_nth(1)) {
+            (Some(Token::Lifetime(lifetime)), _) => {
+                self.pos += 1;
+                Ok(GenericArg::Lifetime(lifetime.to_owned()))
+            }
+            (Some(Token::Ident(name)), Some(Token::Punct("="))) => {
+                self.pos += 2;
+                Ok(GenericArg::Binding(name.to_owned(), self.parse_type()?))
+            }
+            (Some(Token::Literal(_) | Token::Punct("{" | "-")), _) => {
+                let value = self.verbatim_until(&[",", ">"])?;
+                Ok(GenericArg::Const(value.to_owned()))
+            }
+            _ => Ok(GenericArg::Type(self.parse_type()?)),
+        }
+    }
+
+    /// Parses `+` separated bounds, such as `Error + Send + 'static`.
+    pub(crate) fn parse_bounds(&mut self) -> Result<Vec<TypeBound>, TypeParseError> {
+        let mut bounds = vec![self.parse_bound()?];
+
+        while self.eat_punct("+") {
+            bounds.push(self.parse_bound()?);
+        }
+
+        Ok(bounds)
+    }
+
+    /// Parses a single bound, such as `Send`, `?Sized`,
+    /// `for<'a> Fn(&'a str)` or `'static`.
+    pub(crate) fn parse_bound(&mut self) -> Result<TypeBound, TypeParseError> {
+        match self.peek() {
+            Some(Token::Lifetime(_)) => {
+                Ok(TypeBound::Lifetime(self.expect_lifetime()?.to_owned()))
+            }
+            Some(Token::Punct("(")) => {
+                self.pos += 1;
+                let bound = self.parse_bound()?;
+                self.expect_punct(")")?;
+                Ok(bound)
+            }
+            Some(Token::Punct("?")) => {
+                self.pos += 1;
+                Ok(TypeBound::Maybe(Type::Path(self.parse_path()?)))
+            }
+            Some(Token::Ident("for")) => {
+                let lifetimes = self.parse_for_lifetimes()?;
+                let ty = Type::Path(self.parse_path()?);
+                Ok(TypeBound::HigherRanked { lifetimes, ty })
+            }
+            _ => Ok(TypeBound::Trait(Type::Path(self.parse_path()?))),
+        }
+    }
+
+    /// Parses the `for<'a, 'b>` binder of a higher-ranked bound.
+    fn parse_for_lifetimes(&mut self) -> Result<Vec<String>, TypeParseError> {
+        let mut lifetimes = vec![];
+
+        if !self.eat_keyword("for") {
+            return Ok(lifetimes);
+        }
+
+        self.expect_punct("<")?;
+
+        while !self.is_punct(">") {
+            lifetimes.push(self.expect_lifetime()?.to_owned());
+
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+
+        self.expect_punct(">")?;
+        Ok(lifetimes)
+    }
+
+    /// Parses a predicate of a `where` clause, such as `'a: 'b`,
+    /// `T: ?Sized` or `for<'de> T: Deserialize<'de>`.
+    pub(crate) fn parse_where_predicate(&mut self) -> Result<WherePredicate, TypeParseError> {
+        if let Some(Token::Lifetime(_)) = self.peek() {
+            let mut pred = WherePredicate::lifetime(self.expect_lifetime()?);
+            self.expect_punct(":")?;
+
+            loop {
+                pred = pred.outlives(self.expect_lifetime()?);
+
+                if !self.eat_punct("+") {
+                    break;
+                }
+            }
+
+            return Ok(pred);
+        }
+
+        let lifetimes = self.parse_for_lifetimes()?;
+        let mut pred = WherePredicate::new(self.parse_type()?);
+
+        for lifetime in lifetimes {
+            pred = pred.for_lifetime(lifetime);
+        }
+
+        self.expect_punct(":")?;
+
+        for bound in self.parse_bounds()? {
+            pred = pred.bound(bound);
+        }
+
+        Ok(pred)
+    }
+
+    /// Parses a generic parameter declaration, such as `'a: 'b`,
+    /// `T: Clone = u8` or `const N: usize = 4`.
+    pub(crate) fn parse_generic_param(&mut self) -> Result<GenericParam, TypeParseError> {
+        if let Some(Token::Lifetime(_)) = self.peek() {
+            let mut param = GenericParam::lifetime(self.expect_lifetime()?);
+
+            if self.eat_punct(":") {
+                param = param.outlives(self.expect_lifetime()?);
+
+                while self.eat_punct("+") {
+                    param = param.outlives(self.expect_lifetime()?);
+                }
+            }
+
+            return Ok(param);
+        }
+
+        if self.eat_keyword("const") {
+            let name = self.expect_ident()?;
+            self.expect_punct(":")?;
+
+            let mut param = GenericParam::const_param(name, self.parse_type()?);
+
+            if self.eat_punct("=") {
+                param = param.default_value(self.verbatim_until(&[])?);
+            }
+
+            return Ok(param);
+        }
+
+        let mut param = GenericParam::type_param(self.expect_ident()?);
+
+        if self.eat_punct(":") {
+            for bound in self.parse_bounds()? {
+                param = param.bound(bound);
+            }
+        }
+
+        if self.eat_punct("=") {
+            param = param.default(self.parse_type()?);
+        }
+
+        Ok(param)
+    }
+
+    /// Parses the tree of a `use` declaration, such as `foo::{self, Bar as Baz}`.
+    pub(crate) fn parse_use_tree(&mut self) -> Result<UseTree, TypeParseError> {
+        if self.eat_punct("*") {
+            return Ok(UseTree::Glob);
+        }
+
+        if self.eat_punct("{") {
+            let mut trees = vec![];
+
+            while !self.is_punct("}") {
+                trees.push(self.parse_use_tree()?);
+
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+
+            self.expect_punct("}")?;
+            return Ok(UseTree::Group(trees));
+        }
+
+        // A leading `::` is kept as an empty first segment.
+        if self.eat_punct("::") {
+            return Ok(UseTree::Path(String::new(), Box::new(self.parse_use_tree()?)));
+        }
+
+        let name = self.expect_ident()?.to_owned();
+
+        if self.eat_punct("::") {
+            Ok(UseTree::Path(name, Box::new(self.parse_use_tree()?)))
+        } else if self.eat_keyword("as") {
+            Ok(UseTree::Rename(name, self.expect_ident()?.to_owned()))
+        } else {
+            Ok(UseTree::Name(name))
+        }
+    }
+}
+
+fn ident_len(src: &str) -> usize {
+    src.find(|c: char| !(c == '_' || c.is_alphanumeric()))
+        .unwrap_or(src.len())
+}
+
+/// Returns the length of the character literal that `src` starts with, like
+/// `'a'` or `'\n'`, if it starts with one.
+fn char_len(src: &str) -> Option<usize> {
+    let mut chars = src.char_indices();
+
+    if chars.next()?.1 != '\'' {
+        return None;
+    }
+
+    let end = match chars.next()? {
+        (_, '\\') => {
+            let (i, c) = chars.next()?;
+            let from = i + c.len_utf8();
+            from + src[from..].find('\'')?
+        }
+        (i, c) => i + c.len_utf8(),
+    };
+
+    src[end..].starts_with('\'').then_some(end + 1)
+}
+
+/// Returns the length of the string literal that `src` starts with,
+/// skipping escaped quotes, or `None` if it isn't terminated.
+fn str_len(src: &str) -> Option<usize> {
+    let mut chars = src.char_indices().skip(1);
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Some(i + 1),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn error<S>(src: &str, offset: usize, msg: S) -> TypeParseError
+where
+    S: Into<String>,
+{
+    TypeParseError {
+        src: src.to_owned(),
+        offset,
+        msg: msg.into(),
+    }
+}
@@ -0,0 +1,395 @@
+use std::fmt::{self, Write};
+
+use crate::expr::Expr;
+use crate::formatter::Formatter;
+use crate::lit::Lit;
+
+
+/// A pattern, as used in `match` arms, `let` bindings and function
+/// arguments.
+///
+/// ```
+/// use codegen::Pat;
+///
+/// let pat = Pat::tuple_struct("Some", [Pat::tuple([Pat::ident("a"), Pat::Rest])]);
+/// assert_eq!(pat.to_string(), "Some((a, ..))");
+///
+/// let pat = Pat::or(["Ordering::Less", "Ordering::Equal"]).reference();
+/// assert_eq!(pat.to_string(), "&(Ordering::Less | Ordering::Equal)");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pat {
+    /// Source text that is written as is.
+    Verbatim(String),
+    /// The wildcard pattern `_`.
+    Wild,
+    /// The rest pattern `..`, within tuple, tuple struct and slice patterns.
+    Rest,
+    /// An identifier binding, like `x`, `ref mut x` or `x @ 1..=5`.
+    Ident {
+        /// Whether or not the binding is `ref`.
+        by_ref: bool,
+        /// Whether or not the binding is `mut`.
+        mutable: bool,
+        /// The bound name.
+        name: String,
+        /// The pattern after `@`, if any.
+        subpat: Option<Box<Pat>>,
+    },
+    /// A literal, like `1` or `"foo"`.
+    Lit(Lit),
+    /// A path, like `None` or `Ordering::Less`.
+    Path(String),
+    /// A range, like `1..=5`, `'a'..` or `..10`.
+    Range {
+        /// The start of the range.
+        start: Option<Box<Expr>>,
+        /// The end of the range.
+        end: Option<Box<Expr>>,
+        /// Whether or not the end is included.
+        inclusive: bool,
+    },
+    /// A tuple, like `(a, b, ..)`.
+    Tuple(Vec<Pat>),
+    /// A tuple struct or variant, like `Some(x)`.
+    TupleStruct {
+        /// The path of the struct or variant.
+        path: String,
+        /// The element patterns.
+        elems: Vec<Pat>,
+    },
+    /// A struct or struct variant, like `Point { x, y: 0, .. }`.
+    Struct {
+        /// The path of the struct or variant.
+        path: String,
+        /// The field names and their patterns.
+        fields: Vec<(String, Pat)>,
+        /// Whether or not the remaining fields are ignored with `..`.
+        rest: bool,
+    },
+    /// An or-pattern, like `A | B`.
+    Or(Vec<Pat>),
+    /// A slice, like `[first, .., last]`.
+    Slice(Vec<Pat>),
+    /// A reference, like `&x` or `&mut x`.
+    Reference {
+        /// Whether or not the reference is mutable.
+        mutable: bool,
+        /// The referenced pattern.
+        pat: Box<Pat>,
+    },
+}
+
+impl Pat {
+    /// Returns a binding of `name`.
+    pub fn ident<S>(name: S) -> Self
+    where
+        S: ToString,
+    {
+        Pat::Ident {
+            by_ref: false,
+            mutable: false,
+            name: name.to_string(),
+            subpat: None,
+        }
+    }
+
+    /// Returns a `mut` binding of `name`.
+    pub fn ident_mut<S>(name: S) -> Self
+    where
+        S: ToString,
+    {
+        Pat::Ident {
+            by_ref: false,
+            mutable: true,
+            name: name.to_string(),
+            subpat: None,
+        }
+    }
+
+    /// Returns a binding of `name` to whatever matches `subpat`, like
+    /// `x @ 1..=5`.
+    pub fn bind<S, P>(name: S, subpat: P) -> Self
+    where
+        S: ToString,
+        P: Into<Pat>,
+    {
+        Pat::Ident {
+            by_ref: false,
+            mutable: false,
+            name: name.to_string(),
+            subpat: Some(Box::new(subpat.into())),
+        }
+    }
+
+    /// Returns a literal pattern.
+    pub fn lit<T>(lit: T) -> Self
+    where
+        T: Into<Lit>,
+    {
+        Pat::Lit(lit.into())
+    }
+
+    /// Returns a path pattern.
+    pub fn path<S>(path: S) -> Self
+    where
+        S: ToString,
+    {
+        Pat::Path(path.to_string())
+    }
+
+    /// Returns a range pattern between two optional bounds.
+    pub fn range<S, E>(start: Option<S>, end: Option<E>, inclusive: bool) -> Self
+    where
+        S: Into<Expr>,
+        E: Into<Expr>,
+    {
+        Pat::Range {
+            start: start.map(|start| Box::new(start.into())),
+            end: end.map(|end| Box::new(end.into())),
+            inclusive,
+        }
+    }
+
+    /// Returns a tuple pattern.
+    pub fn tuple<I, P>(elems: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Pat>,
+    {
+        Pat::Tuple(elems.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns a tuple struct or tuple variant pattern.
+    pub fn tuple_struct<S, I, P>(path: S, elems: I) -> Self
+    where
+        S: ToString,
+        I: IntoIterator<Item = P>,
+        P: Into<Pat>,
+    {
+        Pat::TupleStruct {
+            path: path.to_string(),
+            elems: elems.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns a struct or struct variant pattern, ending with `..` when
+    /// `rest` is set.
+    pub fn struct_pat<S, I, N, P>(path: S, fields: I, rest: bool) -> Self
+    where
+        S: ToString,
+        I: IntoIterator<Item = (N, P)>,
+        N: ToString,
+        P: Into<Pat>,
+    {
+        Pat::Struct {
+            path: path.to_string(),
+            fields: fields.into_iter()
+                .map(|(name, pat)| (name.to_string(), pat.into()))
+                .collect(),
+            rest,
+        }
+    }
+
+    /// Returns an or-pattern.
+    pub fn or<I, P>(pats: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Pat>,
+    {
+        Pat::Or(pats.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns a slice pattern.
+    pub fn slice<I, P>(elems: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Pat>,
+    {
+        Pat::Slice(elems.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns a pattern matching a shared reference to this pattern.
+    pub fn reference(self) -> Self {
+        Pat::Reference { mutable: false, pat: Box::new(self) }
+    }
+
+    /// Returns a pattern matching a mutable reference to this pattern.
+    pub fn reference_mut(self) -> Self {
+        Pat::Reference { mutable: true, pat: Box::new(self) }
+    }
+
+    /// Formats the pattern, wrapped in parentheses if it is an or-pattern,
+    /// which can't appear directly after `@` or as a function argument.
+    pub(crate) fn fmt_nested(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if matches!(self, Pat::Or(_)) {
+            write!(fmt, "(")?;
+            self.fmt(fmt)?;
+            write!(fmt, ")")
+        } else {
+            self.fmt(fmt)
+        }
+    }
+
+    /// Formats the pattern using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Pat::Verbatim(src) | Pat::Path(src) => write!(fmt, "{}", src),
+            Pat::Lit(lit) => lit.fmt(fmt),
+            Pat::Wild => write!(fmt, "_"),
+            Pat::Rest => write!(fmt, ".."),
+            Pat::Ident { by_ref, mutable, name, subpat } => {
+                if *by_ref {
+                    write!(fmt, "ref ")?;
+                }
+
+                if *mutable {
+                    write!(fmt, "mut ")?;
+                }
+
+                write!(fmt, "{}", name)?;
+
+                if let Some(subpat) = subpat {
+                    write!(fmt, " @ ")?;
+                    subpat.fmt_nested(fmt)?;
+                }
+
+                Ok(())
+            }
+            Pat::Range { start, end, inclusive } => {
+                if let Some(start) = start {
+                    start.fmt(fmt)?;
+                }
+
+                write!(fmt, "{}", if *inclusive { "..=" } else { ".." })?;
+
+                if let Some(end) = end {
+                    end.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+            Pat::Tuple(elems) => {
+                // `(x,)` is a tuple, `(x)` is not
+                let close = if elems.len() == 1 && elems[0] != Pat::Rest { ",)" } else { ")" };
+                fmt_list("(", elems, close, fmt)
+            }
+            Pat::TupleStruct { path, elems } => {
+                write!(fmt, "{}", path)?;
+                fmt_list("(", elems, ")", fmt)
+            }
+            Pat::Struct { path, fields, rest } => {
+                write!(fmt, "{} {{", path)?;
+
+                if fields.is_empty() && !rest {
+                    return write!(fmt, "}}");
+                }
+
+                for (i, (name, pat)) in fields.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, ",")?;
+                    }
+
+                    write!(fmt, " ")?;
+
+                    // `Foo { x: x }` and `Foo { x: ref x }` are written
+                    // as `Foo { x }` and `Foo { ref x }`
+                    match pat {
+                        Pat::Ident { name: ident, subpat: None, .. } if ident == name => {
+                            pat.fmt(fmt)?;
+                        }
+                        _ => {
+                            write!(fmt, "{}: ", name)?;
+                            pat.fmt(fmt)?;
+                        }
+                    }
+                }
+
+                if *rest {
+                    if !fields.is_empty() {
+                        write!(fmt, ",")?;
+                    }
+                    write!(fmt, " ..")?;
+                }
+
+                write!(fmt, " }}")
+            }
+            Pat::Or(pats) => {
+                for (i, pat) in pats.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, " | ")?;
+                    }
+                    pat.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+            Pat::Slice(elems) => fmt_list("[", elems, "]", fmt),
+            Pat::Reference { mutable, pat } => {
+                write!(fmt, "&")?;
+
+                if *mutable {
+                    write!(fmt, "mut ")?;
+                }
+
+                // `&(mut x)` would otherwise read as `&mut x`, and `&1..=5`
+                // isn't allowed
+                let needs_parens = matches!(
+                    **pat,
+                    Pat::Or(_) | Pat::Range { .. } | Pat::Ident { by_ref: false, mutable: true, .. }
+                );
+
+                if needs_parens {
+                    write!(fmt, "(")?;
+                    pat.fmt(fmt)?;
+                    write!(fmt, ")")
+                } else {
+                    pat.fmt(fmt)
+                }
+            }
+        }
+    }
+}
+
+/// Formats a comma separated list of patterns between `open` and `close`.
+fn fmt_list(open: &str, pats: &[Pat], close: &str, fmt: &mut Formatter<'_>) -> fmt::Result {
+    write!(fmt, "{}", open)?;
+
+    for (i, pat) in pats.iter().enumerate() {
+        if i != 0 {
+            write!(fmt, ", ")?;
+        }
+        pat.fmt(fmt)?;
+    }
+
+    write!(fmt, "{}", close)
+}
+
+impl fmt::Display for Pat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        Pat::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
+
+impl<S> From<S> for Pat
+where
+    S: AsRef<str>
+{
+    fn from(src: S) -> Self {
+        Pat::Verbatim(src.as_ref().to_owned())
+    }
+}
+
+impl From<Lit> for Pat {
+    fn from(src: Lit) -> Self {
+        Pat::Lit(src)
+    }
+}
+
+impl<'a> From<&'a Pat> for Pat {
+    fn from(src: &'a Pat) -> Self {
+        src.clone()
+    }
+}
@@ -1,25 +1,41 @@
 use std::fmt::{self, Write};
 
+use std::collections::{HashMap, HashSet};
+
 use indexmap::IndexMap;
 
+use crate::error::CodegenError;
 use crate::formatter::Formatter;
 use crate::function::Function;
-use crate::import::Import;
+use crate::import::{ExternCrate, Import, ImportOrder, UseTree};
 use crate::item::Item;
 use crate::module::Module;
 
+use crate::const_gen::Const;
 use crate::enum_gen::Enum;
+use crate::extern_block::ExternBlock;
 use crate::impl_gen::Impl;
+use crate::static_gen::Static;
 use crate::struct_gen::Struct;
 use crate::trait_gen::Trait;
+use crate::type_alias::TypeAlias;
+use crate::union_gen::Union;
+use crate::validate::{Diagnostic, check_duplicates, item_path};
+use crate::type_def::{PathSegment, Type, TypePath};
+use crate::vis::Vis;
+use crate::visit::{Text, Visit, may_use_traits, referenced_idents};
 
 /// Defines a scope.
 ///
 /// A scope contains modules, types, etc...
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Scope {
+    /// `extern crate` declarations
+    extern_crates: Vec<ExternCrate>,
     /// Imports
-    imports: IndexMap<String, IndexMap<String, Import>>,
+    imports: Vec<Import>,
+    /// Order in which the imports are formatted
+    import_order: ImportOrder,
     /// Contents of the documentation,
     items: Vec<Item>,
 }
@@ -28,7 +44,9 @@ impl Scope {
     /// Returns a new scope
     pub fn new() -> Self {
         Scope {
-            imports: IndexMap::new(),
+            extern_crates: vec![],
+            imports: vec![],
+            import_order: ImportOrder::default(),
             items: vec![],
         }
     }
@@ -41,11 +59,236 @@ impl Scope {
         // handle cases where the caller wants to refer to a type namespaced
         // within the containing namespace, like "a::B".
         let ty = ty.split("::").next().unwrap_or(ty);
-        self.imports
-            .entry(path.to_string())
-            .or_insert(IndexMap::new())
-            .entry(ty.to_string())
-            .or_insert_with(|| Import::new(path, ty))
+        self.push_import(Import::new(path, ty))
+    }
+
+    /// Import a `use` tree into the scope, such as `std::io::{self, Read}`
+    /// or `std::fmt::Result as FmtResult`.
+    ///
+    /// Imports sharing a module path are merged into a single `use`
+    /// statement when formatted, and names that are already imported are
+    /// skipped.
+    pub fn import_tree<T>(&mut self, tree: T) -> &mut Import
+    where
+        T: Into<UseTree>,
+    {
+        self.push_import(Import::new_tree(tree))
+    }
+
+    /// Push an import, returning a mutable reference to it. If an identical
+    /// tree was already imported, the existing import is returned instead.
+    pub fn push_import(&mut self, item: Import) -> &mut Import {
+        let idx = match self.imports.iter().position(|import| import.tree() == item.tree()) {
+            Some(idx) => idx,
+            None => {
+                self.imports.push(item);
+                self.imports.len() - 1
+            }
+        };
+
+        &mut self.imports[idx]
+    }
+
+    /// Set the order in which the scope's imports are formatted.
+    ///
+    /// This only applies to this scope, not to the scopes of any modules
+    /// defined within it.
+    pub fn set_import_order(&mut self, order: ImportOrder) -> &mut Self {
+        self.import_order = order;
+        self
+    }
+
+    /// Push a new `extern crate` declaration, returning a mutable reference
+    /// to it.
+    pub fn extern_crate<S>(&mut self, name: S) -> &mut ExternCrate
+    where
+        S: AsRef<str>,
+    {
+        self.extern_crates.push(ExternCrate::new(name));
+        self.extern_crates.last_mut().unwrap()
+    }
+
+    /// Shortens the fully-qualified type paths used by the scope's items,
+    /// such as `std::collections::HashMap<K, V>`, to their last segment and
+    /// imports them.
+    ///
+    /// Every type in fields, arguments, return types, bounds and generics is
+    /// visited. A path is left qualified if its last segment would clash with
+    /// an item defined in the scope, a different import, a name from the
+    /// standard prelude, or a name that is already used unqualified. Paths to
+    /// the prelude items themselves, like `std::iter::IntoIterator`, are
+    /// shortened without an import. Modules defined in the scope collect
+    /// their own imports.
+    pub fn collect_imports(&mut self) -> &mut Self {
+        let mut names = self.imported_names();
+
+        // Importing e.g. `std::fmt::Result` would shadow the prelude's
+        // `Result` for the whole scope.
+        for (name, path) in PRELUDE {
+            names.entry((*name).to_owned()).or_insert_with(|| Some((*path).to_owned()));
+        }
+
+        // Names used unqualified refer to something other than what a
+        // shortened path would, e.g. a prelude type or generic parameter.
+        for item in &mut self.items {
+            item.visit_types_mut(&mut |ty| ty.visit_paths_mut(&mut |path| {
+                if let [segment] = path.segments() {
+                    names.entry(segment.name().to_owned()).or_insert(None);
+                }
+            }));
+        }
+
+        let mut imports = vec![];
+
+        let mut shorten = |path: &mut TypePath| {
+            let Some((last, modules)) = path.segments().split_last() else {
+                return;
+            };
+
+            if modules.is_empty() || !modules.iter().all(is_module_segment) {
+                return;
+            }
+
+            let mut full = if path.is_global() { "::".to_owned() } else { String::new() };
+
+            for module in modules {
+                full.push_str(module.name());
+                full.push_str("::");
+            }
+            full.push_str(last.name());
+
+            match names.get(last.name()) {
+                Some(Some(imported)) if *imported == full => {}
+                Some(_) => return,
+                None => {
+                    names.insert(last.name().to_owned(), Some(full.clone()));
+                    imports.push(full);
+                }
+            }
+
+            let last = path.segments_mut().pop().unwrap();
+            *path = TypePath::new([last]);
+        };
+
+        for item in &mut self.items {
+            match item {
+                Item::Module(module) => {
+                    module.scope().collect_imports();
+                }
+                item => item.visit_types_mut(&mut |ty| ty.visit_paths_mut(&mut shorten)),
+            }
+        }
+
+        for import in imports {
+            self.import_tree(UseTree::name(import));
+        }
+
+        self
+    }
+
+    /// Removes the imports that nothing in the scope refers to.
+    ///
+    /// A name is considered referenced if it starts a path in a type or bound,
+    /// or appears in an attribute or derive. If `scan_bodies` is set, the
+    /// identifiers in function bodies, `const` and `static` values and raw
+    /// items are counted as well.
+    ///
+    /// An import can also be needed for the methods or macros of a trait
+    /// without its name ever appearing, so nothing is removed when the scope
+    /// holds code that could use one: any code when `scan_bodies` isn't set,
+    /// and otherwise verbatim lines and raw items, or code that calls a
+    /// method, a qualified path or a macro.
+    ///
+    /// Imports that aren't private (re-exports), globs and `_` imports are
+    /// kept. Modules defined in the scope prune their own imports, and what
+    /// they refer to doesn't keep the imports of this scope.
+    pub fn prune_imports(&mut self, scan_bodies: bool) -> &mut Self {
+        let mut referenced = HashSet::new();
+        let mut keep_all = false;
+
+        for item in &mut self.items {
+            // A module's items have imports of their own, so only its
+            // attributes can refer to the imports of this scope.
+            if let Item::Module(module) = item {
+                module.scope().prune_imports(scan_bodies);
+                module.visit_text(&mut |_, attr| {
+                    referenced.extend(referenced_idents(attr).into_iter().map(str::to_owned));
+                });
+                continue;
+            }
+
+            item.visit_types_mut(&mut |ty| {
+                if let Type::Verbatim(src) = ty {
+                    referenced.extend(referenced_idents(src).into_iter().map(str::to_owned));
+                }
+
+                ty.visit_paths_mut(&mut |path| {
+                    match path.segments().first() {
+                        Some(first) if !path.is_global() => {
+                            referenced.insert(first.name().to_owned());
+                        }
+                        _ => {}
+                    }
+                });
+            });
+
+            item.visit_text(&mut |kind, text| {
+                match kind {
+                    Text::Attr => {}
+                    Text::Body if scan_bodies => keep_all |= may_use_traits(text),
+                    Text::Body | Text::Verbatim => keep_all = true,
+                }
+
+                if kind == Text::Attr || scan_bodies {
+                    referenced.extend(referenced_idents(text).into_iter().map(str::to_owned));
+                }
+            });
+        }
+
+        if keep_all {
+            return self;
+        }
+
+        self.imports.retain_mut(|import| {
+            !import.is_private() || import.retain_names(&|name| referenced.contains(name))
+        });
+
+        self
+    }
+
+    /// Maps the names brought into the type namespace of the scope to the
+    /// path they were imported from, or `None` for names defined locally.
+    fn imported_names(&self) -> HashMap<String, Option<String>> {
+        let mut names = HashMap::new();
+
+        for item in &self.items {
+            if let Some(name) = item.type_name() {
+                names.insert(name.to_owned(), None);
+            }
+        }
+
+        for krate in &self.extern_crates {
+            names.insert(krate.imported_name().to_owned(), None);
+        }
+
+        for import in &self.imports {
+            for (path, leaf) in import.tree().flatten() {
+                let (name, full) = match leaf {
+                    UseTree::Name(name) if name == "self" => match path.last() {
+                        Some(name) => (*name, Some(path.join("::"))),
+                        None => continue,
+                    },
+                    UseTree::Name(name) if path.is_empty() => (name.as_str(), Some(name.clone())),
+                    UseTree::Name(name) => (name.as_str(), Some(format!("{}::{}", path.join("::"), name))),
+                    UseTree::Rename(_, alias) => (alias.as_str(), None),
+                    _ => continue,
+                };
+
+                names.entry(name.to_owned()).or_insert(full);
+            }
+        }
+
+        names
     }
 
     /// Push a new module definition, returning a mutable reference to it.
@@ -86,7 +329,7 @@ impl Scope {
     }
 
     /// Returns a mutable reference to a module if it is exists in this scope.
-    pub fn get_module<'a, S>(&self, name: S) -> Option<&Module>
+    pub fn get_module<S>(&self, name: S) -> Option<&Module>
     where
         S: AsRef<str>
     {
@@ -127,9 +370,32 @@ impl Scope {
     ///
     /// [`get_or_new_module`]: #method.get_or_new_module
     pub fn push_module(&mut self, item: Module) -> &mut Self {
-        assert!(self.get_module(item.name()).is_none());
+        self.try_push_module(item).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Push a new module definition, returning a mutable reference to it, or
+    /// an error if a module with the same name is already defined.
+    pub fn try_new_module<S>(&mut self, name: S) -> Result<&mut Module, CodegenError>
+    where
+        S: AsRef<str>
+    {
+        self.try_push_module(Module::new(name))?;
+
+        match *self.items.last_mut().unwrap() {
+            Item::Module(ref mut v) => Ok(v),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a module definition, or return an error if a module with the same
+    /// name is already defined.
+    pub fn try_push_module(&mut self, item: Module) -> Result<&mut Self, CodegenError> {
+        if self.get_module(item.name()).is_some() {
+            return Err(CodegenError::DuplicateModule { path: item.name().to_owned() });
+        }
+
         self.items.push(Item::Module(item));
-        self
+        Ok(self)
     }
 
     /// Push a new struct definition, returning a mutable reference to it.
@@ -148,6 +414,25 @@ impl Scope {
         self
     }
 
+    /// Push a new union definition, returning a mutable reference to it.
+    pub fn new_union<S>(&mut self, name: S) -> &mut Union
+    where
+        S: AsRef<str>,
+    {
+        self.push_union(Union::new(name));
+
+        match *self.items.last_mut().unwrap() {
+            Item::Union(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a union definition
+    pub fn push_union(&mut self, item: Union) -> &mut Self {
+        self.items.push(Item::Union(item));
+        self
+    }
+
     /// Push a new function definition, returning a mutable reference to it.
     pub fn new_fn(&mut self, name: &str) -> &mut Function {
         self.push_fn(Function::new(name));
@@ -212,6 +497,86 @@ impl Scope {
         self
     }
 
+    /// Push a new `const` definition, returning a mutable reference to it.
+    pub fn new_const<S, T>(&mut self, name: S, ty: T) -> &mut Const
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.push_const(Const::new(name, ty));
+
+        match *self.items.last_mut().unwrap() {
+            Item::Const(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a `const` definition.
+    pub fn push_const(&mut self, item: Const) -> &mut Self {
+        self.items.push(Item::Const(item));
+        self
+    }
+
+    /// Push a new `static` definition, returning a mutable reference to it.
+    pub fn new_static<S, T>(&mut self, name: S, ty: T) -> &mut Static
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.push_static(Static::new(name, ty));
+
+        match *self.items.last_mut().unwrap() {
+            Item::Static(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a `static` definition.
+    pub fn push_static(&mut self, item: Static) -> &mut Self {
+        self.items.push(Item::Static(item));
+        self
+    }
+
+    /// Push a new type alias, returning a mutable reference to it.
+    pub fn new_type_alias<S, T>(&mut self, name: S, target: T) -> &mut TypeAlias
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.push_type_alias(TypeAlias::new(name, target));
+
+        match *self.items.last_mut().unwrap() {
+            Item::TypeAlias(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a type alias.
+    pub fn push_type_alias(&mut self, item: TypeAlias) -> &mut Self {
+        self.items.push(Item::TypeAlias(item));
+        self
+    }
+
+    /// Push a new `extern` block with the given ABI, returning a mutable
+    /// reference to it.
+    pub fn new_extern_block<S>(&mut self, abi: S) -> &mut ExternBlock
+    where
+        S: AsRef<str>,
+    {
+        self.push_extern_block(ExternBlock::new(abi));
+
+        match *self.items.last_mut().unwrap() {
+            Item::ExternBlock(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push an `extern` block.
+    pub fn push_extern_block(&mut self, item: ExternBlock) -> &mut Self {
+        self.items.push(Item::ExternBlock(item));
+        self
+    }
+
     /// Push a raw string to the scope.
     ///
     /// This string will be included verbatim in the formatted string.
@@ -220,7 +585,24 @@ impl Scope {
         self
     }
 
+    /// Push an item.
+    pub fn push_item<I>(&mut self, item: I) -> &mut Self
+    where
+        I: Into<Item>,
+    {
+        self.items.push(item.into());
+        self
+    }
+
     /// Return a string representation of the scope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an item can't be formatted. See [`try_to_string`] for a
+    /// fallible version.
+    ///
+    /// [`try_to_string`]: #method.try_to_string
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         let mut ret = String::new();
 
@@ -234,83 +616,417 @@ impl Scope {
         ret
     }
 
+    /// Return a string representation of the scope, or the first error that
+    /// prevents it from being formatted.
+    ///
+    /// Unlike [`to_string`], this doesn't panic on invalid items.
+    ///
+    /// [`to_string`]: #method.to_string
+    pub fn try_to_string(&self) -> Result<String, CodegenError> {
+        self.check()?;
+        Ok(self.to_string())
+    }
+
+    /// Checks the whole item tree for problems, such as duplicate or invalid
+    /// names, and returns every one that is found.
+    ///
+    /// Diagnostics with [`Severity::Error`] describe code that won't compile,
+    /// while [`Severity::Warning`] is used for code that may not compile, like
+    /// deriving `Copy` without `Clone`.
+    ///
+    /// [`Severity::Error`]: crate::Severity::Error
+    /// [`Severity::Warning`]: crate::Severity::Warning
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diags = vec![];
+        self.validate_in("", &mut diags);
+        diags
+    }
+
+    /// Reports problems with the items of the scope, which belong to
+    /// `module`.
+    pub(crate) fn validate_in(&self, module: &str, diags: &mut Vec<Diagnostic>) {
+        let path = |name: &str| item_path(module, name);
+
+        // Types and values live in separate namespaces.
+        check_duplicates(self.items.iter().filter_map(Item::type_name), "name", path, diags);
+        check_duplicates(self.items.iter().filter_map(Item::value_name), "name", path, diags);
+
+        for item in &self.items {
+            item.validate(module, diags);
+            item.validate_types(module, diags);
+        }
+    }
+
+    /// Returns the names defined by the items of this scope.
+    pub(crate) fn item_names(&self) -> impl Iterator<Item = &str> {
+        self.items.iter().flat_map(|item| item.type_name().into_iter().chain(item.value_name()))
+    }
+
+    /// Checks that every item in the scope can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        self.items.iter().try_for_each(Item::check)
+    }
+
     /// Formats the scope using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.fmt_imports(fmt)?;
 
-        if !self.imports.is_empty() {
-            write!(fmt, "\n")?;
+        if !self.imports.is_empty() || !self.extern_crates.is_empty() {
+            writeln!(fmt)?;
         }
 
         for (i, item) in self.items.iter().enumerate() {
             if i != 0 {
-                write!(fmt, "\n")?;
+                writeln!(fmt)?;
             }
 
-            match *item {
-                Item::Module(ref v) => v.fmt(fmt)?,
-                Item::Struct(ref v) => v.fmt(fmt)?,
-                Item::Function(ref v) => v.fmt(false, fmt)?,
-                Item::Trait(ref v) => v.fmt(fmt)?,
-                Item::Enum(ref v) => v.fmt(fmt)?,
-                Item::Impl(ref v) => v.fmt(fmt)?,
-                Item::Raw(ref v) => {
-                    write!(fmt, "{}\n", v)?;
-                }
-            }
+            item.fmt(fmt)?;
         }
 
         Ok(())
     }
 
     fn fmt_imports(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let sorted = self.import_order != ImportOrder::Insertion;
+        let grouped = self.import_order == ImportOrder::Grouped;
+
+        let mut crates: Vec<&ExternCrate> = self.extern_crates.iter().collect();
+
+        if sorted {
+            crates.sort_by_key(|krate| krate.name());
+        }
+
+        for krate in crates {
+            krate.fmt(fmt)?;
+        }
+
         // First, collect all visibilities
         let mut visibilities = vec![];
 
-        for (_, imports) in &self.imports {
-            for (_, import) in imports {
-                if !visibilities.contains(&import.vis) {
-                    visibilities.push(import.vis.clone());
-                }
+        for import in &self.imports {
+            if !visibilities.contains(&import.vis) {
+                visibilities.push(import.vis);
             }
         }
 
-        let mut tys = vec![];
+        if sorted {
+            visibilities.sort_by_key(|vis| vis_rank(*vis));
+        }
+
+        let mut uses = vec![];
 
-        // Loop over all visibilities and format the associated imports
         for vis in &visibilities {
-            for (path, imports) in &self.imports {
-                tys.clear();
+            uses.extend(self.collect_uses(*vis).into_iter().map(|use_| (*vis, use_)));
+        }
+
+        if sorted {
+            for (_, use_) in &mut uses {
+                use_.tree.sort();
+            }
+
+            // Groups come first, so that `pub use` and `use` statements of the
+            // same group are formatted together.
+            uses.sort_by_cached_key(|(vis, use_)| {
+                (if grouped { use_.group() } else { 0 }, vis_rank(*vis), use_.to_string())
+            });
+        }
+
+        let mut prev_group = None;
+
+        for (vis, use_) in &uses {
+            if grouped {
+                let group = use_.group();
+
+                if prev_group.is_some_and(|prev| prev != group) {
+                    writeln!(fmt)?;
+                }
+                prev_group = Some(group);
+            }
+
+            vis.fmt(fmt)?;
+            writeln!(fmt, "use {};", use_)?;
+        }
+
+        Ok(())
+    }
+
+    /// Collects the imports with the given visibility into `use` statements.
+    ///
+    /// Each import is keyed by the longest module path shared by its leaves,
+    /// and the imports with the same key are merged into a single nested
+    /// tree. Names that were already imported, including `a::b` and
+    /// `a::b::{self}`, are only imported once.
+    fn collect_uses(&self, vis: Vis) -> Vec<UseStmt<'_>> {
+        let mut uses: Vec<UseStmt<'_>> = vec![];
+        let mut seen = HashSet::new();
 
-                for (ty, import) in imports {
-                    if *vis == import.vis {
-                        tys.push(ty);
+        for import in self.imports.iter().filter(|import| import.vis == vis) {
+            // Leaves that don't share a first segment, as in `{a::B, c::D}`,
+            // have nothing to be merged under.
+            let mut roots: IndexMap<Option<&str>, Vec<(Vec<&str>, &UseTree)>> = IndexMap::new();
+
+            for (path, leaf) in import.tree().flatten() {
+                if seen.insert(leaf_key(&path, leaf)) {
+                    roots.entry(path.first().copied()).or_default().push((path, leaf));
+                }
+            }
+
+            for (root, leaves) in roots {
+                // Top-level names like `use serde;` can't be merged.
+                let stem = match root {
+                    Some(_) => common_prefix(leaves.iter().map(|(path, _)| path.as_slice())),
+                    None => vec![],
+                };
+
+                let idx = match uses.iter().position(|use_| !stem.is_empty() && use_.path == stem) {
+                    Some(idx) => idx,
+                    None => {
+                        uses.push(UseStmt { path: stem.clone(), tree: UseNode::default() });
+                        uses.len() - 1
                     }
+                };
+
+                for (path, leaf) in leaves {
+                    uses[idx].tree.insert(&path[stem.len()..], leaf);
                 }
+            }
+        }
+
+        uses
+    }
+}
 
-                if !tys.is_empty() {
-                    vis.fmt(fmt)?;
+/// A single formatted `use` statement.
+struct UseStmt<'a> {
+    path: Vec<&'a str>,
+    tree: UseNode<'a>,
+}
 
-                    write!(fmt, "use {}::", path)?;
+/// The tree imported by a `use` statement, below its module path.
+#[derive(Default)]
+struct UseNode<'a> {
+    entries: Vec<UseEntry<'a>>,
+}
 
-                    if tys.len() > 1 {
-                        write!(fmt, "{{")?;
+enum UseEntry<'a> {
+    /// The module itself, `self`.
+    SelfName,
+    /// A renamed or glob import.
+    Leaf(&'a UseTree),
+    /// A name imported from the module, with whatever is imported from
+    /// within it.
+    Child(&'a str, UseNode<'a>),
+}
 
-                        for (i, ty) in tys.iter().enumerate() {
-                            if i != 0 {
-                                write!(fmt, ", ")?;
-                            }
-                            write!(fmt, "{}", ty)?;
-                        }
+impl<'a> UseNode<'a> {
+    /// Adds `leaf`, imported from the path `rel` below this node.
+    fn insert(&mut self, rel: &[&'a str], leaf: &'a UseTree) {
+        match (rel.split_first(), leaf) {
+            (Some((first, rest)), _) => self.child(first).insert(rest, leaf),
+            (None, UseTree::Name(name)) if name == "self" => self.insert_self(),
+            (None, UseTree::Name(name)) => self.child(name).insert_self(),
+            (None, leaf) => self.entries.push(UseEntry::Leaf(leaf)),
+        }
+    }
+
+    fn insert_self(&mut self) {
+        if !self.entries.iter().any(|entry| matches!(entry, UseEntry::SelfName)) {
+            self.entries.insert(0, UseEntry::SelfName);
+        }
+    }
+
+    fn child(&mut self, name: &'a str) -> &mut UseNode<'a> {
+        let idx = self.entries.iter()
+            .position(|entry| matches!(entry, UseEntry::Child(child, _) if *child == name));
+
+        let idx = idx.unwrap_or_else(|| {
+            self.entries.push(UseEntry::Child(name, UseNode::default()));
+            self.entries.len() - 1
+        });
+
+        match self.entries[idx] {
+            UseEntry::Child(_, ref mut node) => node,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns whether the node only imports the module itself.
+    fn is_self(&self) -> bool {
+        matches!(self.entries[..], [UseEntry::SelfName])
+    }
+
+    /// Sorts the entries of the tree alphabetically.
+    fn sort(&mut self) {
+        for entry in &mut self.entries {
+            if let UseEntry::Child(_, node) = entry {
+                node.sort();
+            }
+        }
+
+        // rustfmt sorts `self` before any other name
+        self.entries.sort_by_cached_key(|entry| {
+            (!matches!(entry, UseEntry::SelfName), entry.to_string())
+        });
+    }
+}
+
+impl UseStmt<'_> {
+    /// Returns the import group of the statement: 0 for the standard
+    /// library, 1 for external crates and 2 for the local crate.
+    fn group(&self) -> u8 {
+        let first = match (self.path.first(), self.tree.entries.first()) {
+            (Some(first), _) => *first,
+            (None, Some(UseEntry::Child(name, _))) => name,
+            (None, Some(UseEntry::Leaf(UseTree::Name(name) | UseTree::Rename(name, _)))) => name.as_str(),
+            _ => "",
+        };
 
-                        write!(fmt, "}};\n")?;
-                    } else if tys.len() == 1 {
-                        write!(fmt, "{};\n", tys[0])?;
+        match first {
+            "std" | "core" | "alloc" => 0,
+            "crate" | "super" | "self" => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for UseStmt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for segment in &self.path {
+            write!(f, "{}::", segment)?;
+        }
+
+        match self.tree.entries[..] {
+            // Top-level names are never merged
+            [ref entry] if self.path.is_empty() => write!(f, "{}", entry),
+            _ => write!(f, "{}", self.tree),
+        }
+    }
+}
+
+impl fmt::Display for UseNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `self` can only be imported from within braces
+        match self.entries[..] {
+            [ref entry] if !self.is_self() => write!(f, "{}", entry),
+            _ => {
+                write!(f, "{{")?;
+
+                for (i, entry) in self.entries.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
                     }
+                    write!(f, "{}", entry)?;
                 }
+
+                write!(f, "}}")
             }
         }
+    }
+}
 
-        Ok(())
+impl fmt::Display for UseEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UseEntry::SelfName => write!(f, "self"),
+            UseEntry::Leaf(leaf) => write!(f, "{}", leaf),
+            UseEntry::Child(name, node) if node.is_self() => write!(f, "{}", name),
+            UseEntry::Child(name, node) => write!(f, "{}::{}", name, node),
+        }
+    }
+}
+
+/// Returns what a leaf of a `use` tree imports, so that `a::b` and
+/// `a::b::{self}` are recognized as the same import.
+fn leaf_key<'a>(path: &[&'a str], leaf: &'a UseTree) -> (Vec<&'a str>, Option<&'a UseTree>) {
+    match leaf {
+        UseTree::Name(name) if name == "self" => (path.to_vec(), None),
+        UseTree::Name(name) => ([path, &[name.as_str()]].concat(), None),
+        leaf => (path.to_vec(), Some(leaf)),
+    }
+}
+
+/// Returns the longest prefix shared by all of the given paths.
+fn common_prefix<'a, 'p>(mut paths: impl Iterator<Item = &'p [&'a str]>) -> Vec<&'a str>
+where
+    'a: 'p,
+{
+    let mut prefix = paths.next().unwrap_or_default().to_vec();
+
+    for path in paths {
+        let len = prefix.iter().zip(path).take_while(|(a, b)| a == b).count();
+        prefix.truncate(len);
+    }
+
+    prefix
+}
+
+/// The names brought into every module by the standard prelude, and the
+/// paths they refer to.
+const PRELUDE: &[(&str, &str)] = &[
+    ("Copy", "std::marker::Copy"),
+    ("Send", "std::marker::Send"),
+    ("Sized", "std::marker::Sized"),
+    ("Sync", "std::marker::Sync"),
+    ("Unpin", "std::marker::Unpin"),
+    ("Drop", "std::ops::Drop"),
+    ("Fn", "std::ops::Fn"),
+    ("FnMut", "std::ops::FnMut"),
+    ("FnOnce", "std::ops::FnOnce"),
+    ("Box", "std::boxed::Box"),
+    ("ToOwned", "std::borrow::ToOwned"),
+    ("Clone", "std::clone::Clone"),
+    ("PartialEq", "std::cmp::PartialEq"),
+    ("PartialOrd", "std::cmp::PartialOrd"),
+    ("Eq", "std::cmp::Eq"),
+    ("Ord", "std::cmp::Ord"),
+    ("AsRef", "std::convert::AsRef"),
+    ("AsMut", "std::convert::AsMut"),
+    ("Into", "std::convert::Into"),
+    ("From", "std::convert::From"),
+    ("TryFrom", "std::convert::TryFrom"),
+    ("TryInto", "std::convert::TryInto"),
+    ("Default", "std::default::Default"),
+    ("Iterator", "std::iter::Iterator"),
+    ("Extend", "std::iter::Extend"),
+    ("IntoIterator", "std::iter::IntoIterator"),
+    ("DoubleEndedIterator", "std::iter::DoubleEndedIterator"),
+    ("ExactSizeIterator", "std::iter::ExactSizeIterator"),
+    ("FromIterator", "std::iter::FromIterator"),
+    ("Option", "std::option::Option"),
+    ("Some", "std::option::Option::Some"),
+    ("None", "std::option::Option::None"),
+    ("Result", "std::result::Result"),
+    ("Ok", "std::result::Result::Ok"),
+    ("Err", "std::result::Result::Err"),
+    ("String", "std::string::String"),
+    ("ToString", "std::string::ToString"),
+    ("Vec", "std::vec::Vec"),
+];
+
+/// Whether or not a path segment names a module, and so can be dropped
+/// when the path is imported.
+fn is_module_segment(segment: &PathSegment) -> bool {
+    let name = segment.name();
+    let starts_lowercase = name.is_empty()
+        || name.starts_with(|c: char| c.is_lowercase() || c == '_');
+
+    starts_lowercase
+        && segment.args().is_empty()
+        && segment.fn_inputs().is_none()
+}
+
+/// Sort rank of a visibility, used to make sorted import output deterministic.
+fn vis_rank(vis: Vis) -> u8 {
+    match vis {
+        Vis::Private => 0,
+        Vis::PubSuper => 1,
+        Vis::PubCrate => 2,
+        Vis::Pub => 3,
+    }
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,561 @@
+use std::error::Error;
+use std::fmt;
+
+use serde::ser::{self, Serialize};
+
+use crate::expr::{Delimiter, Expr};
+use crate::lit::Lit;
+
+
+/// Serializes runtime values into Rust expressions that construct them,
+/// like struct literals, enum variants and arrays.
+///
+/// Type names are written as serde reports them, without a path, so the
+/// types must be in scope wherever the expression is used.
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use codegen::{SeqStyle, Serializer};
+///
+/// let mut ser = Serializer::new();
+/// ser.seq_style(SeqStyle::Vec);
+///
+/// let expr = ser.to_expr(&vec![Some(1u8), None]).unwrap();
+/// assert_eq!(expr.to_string(), "vec![Some(1), None]");
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Serializer {
+    seq_style: SeqStyle,
+    map_style: MapStyle,
+}
+
+/// How sequences, like `Vec`s and slices, are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeqStyle {
+    /// A slice, like `&[a, b]`, which can be used in a `const`.
+    #[default]
+    Slice,
+    /// An array, like `[a, b]`.
+    Array,
+    /// A `vec![a, b]`.
+    Vec,
+}
+
+/// How maps are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MapStyle {
+    /// A sequence of key and value tuples, like `&[(k, v)]`, written in the
+    /// [`SeqStyle`] of the serializer.
+    #[default]
+    Tuples,
+    /// A `phf::phf_map! { k => v }` table.
+    Phf,
+}
+
+/// An error raised while serializing a value into an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializeError {
+    msg: String,
+}
+
+impl Serializer {
+    /// Returns a serializer that writes sequences as slices, and maps as
+    /// slices of tuples.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how sequences are written.
+    pub fn seq_style(&mut self, style: SeqStyle) -> &mut Self {
+        self.seq_style = style;
+        self
+    }
+
+    /// Set how maps are written.
+    pub fn map_style(&mut self, style: MapStyle) -> &mut Self {
+        self.map_style = style;
+        self
+    }
+
+    /// Returns an expression that constructs `value`.
+    pub fn to_expr<T>(&self, value: &T) -> Result<Expr, SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn seq(&self, items: Vec<Expr>) -> Expr {
+        match self.seq_style {
+            SeqStyle::Slice => Expr::Array(items).reference(),
+            SeqStyle::Array => Expr::Array(items),
+            SeqStyle::Vec => Expr::macro_call("vec", Delimiter::Bracket, items),
+        }
+    }
+
+    fn map(&self, entries: Vec<(Expr, Expr)>) -> Expr {
+        match self.map_style {
+            MapStyle::Tuples => {
+                let items = entries.into_iter().map(|(k, v)| Expr::Tuple(vec![k, v]));
+                self.seq(items.collect())
+            }
+            MapStyle::Phf => {
+                let items = entries.into_iter().map(|(k, v)| format!("{} => {}", k, v));
+                Expr::macro_call("phf::phf_map", Delimiter::Brace, items)
+            }
+        }
+    }
+}
+
+/// Returns an expression that constructs `value`, with the default
+/// [`Serializer`].
+pub fn to_expr<T>(value: &T) -> Result<Expr, SerializeError>
+where
+    T: Serialize + ?Sized,
+{
+    Serializer::new().to_expr(value)
+}
+
+fn variant_path(name: &str, variant: &str) -> String {
+    format!("{}::{}", name, variant)
+}
+
+fn lit<T>(value: T) -> Expr
+where
+    T: Into<Lit>,
+{
+    Expr::Lit(value.into())
+}
+
+impl<'a> ser::Serializer for &'a Serializer {
+    type Ok = Expr;
+    type Error = SerializeError;
+
+    type SerializeSeq = SerializeSeq<'a>;
+    type SerializeTuple = SerializeSeq<'a>;
+    type SerializeTupleStruct = SerializeSeq<'a>;
+    type SerializeTupleVariant = SerializeSeq<'a>;
+    type SerializeMap = SerializeMap<'a>;
+    type SerializeStruct = SerializeStruct<'a>;
+    type SerializeStructVariant = SerializeStruct<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Expr, SerializeError> {
+        Ok(lit(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Expr, SerializeError> {
+        Ok(Expr::Lit(Lit::byte_str(v)))
+    }
+
+    fn serialize_none(self) -> Result<Expr, SerializeError> {
+        Ok(Expr::path("None"))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Expr, SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(Expr::path("Some").call([value.serialize(self)?]))
+    }
+
+    fn serialize_unit(self) -> Result<Expr, SerializeError> {
+        Ok(Expr::Tuple(vec![]))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Expr, SerializeError> {
+        Ok(Expr::path(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Expr, SerializeError> {
+        Ok(Expr::path(variant_path(name, variant)))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Expr, SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(Expr::path(name).call([value.serialize(self)?]))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Expr, SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(Expr::path(variant_path(name, variant)).call([value.serialize(self)?]))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeSeq<'a>, SerializeError> {
+        Ok(SerializeSeq::new(self, SeqKind::Seq, len.unwrap_or(0)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeSeq<'a>, SerializeError> {
+        Ok(SerializeSeq::new(self, SeqKind::Tuple, len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq<'a>, SerializeError> {
+        Ok(SerializeSeq::new(self, SeqKind::Call(name.to_string()), len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq<'a>, SerializeError> {
+        Ok(SerializeSeq::new(self, SeqKind::Call(variant_path(name, variant)), len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap<'a>, SerializeError> {
+        Ok(SerializeMap {
+            ser: self,
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<SerializeStruct<'a>, SerializeError> {
+        Ok(SerializeStruct::new(self, name.to_string(), len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStruct<'a>, SerializeError> {
+        Ok(SerializeStruct::new(self, variant_path(name, variant), len))
+    }
+}
+
+/// Collects the elements of a sequence, tuple, or tuple struct or variant.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct SerializeSeq<'a> {
+    ser: &'a Serializer,
+    kind: SeqKind,
+    items: Vec<Expr>,
+}
+
+#[derive(Debug)]
+enum SeqKind {
+    Seq,
+    Tuple,
+    Call(String),
+}
+
+impl<'a> SerializeSeq<'a> {
+    fn new(ser: &'a Serializer, kind: SeqKind, len: usize) -> Self {
+        SerializeSeq {
+            ser,
+            kind,
+            items: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.items.push(value.serialize(self.ser)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Expr, SerializeError> {
+        Ok(match self.kind {
+            SeqKind::Seq => self.ser.seq(self.items),
+            SeqKind::Tuple => Expr::Tuple(self.items),
+            SeqKind::Call(path) => Expr::path(path).call(self.items),
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeSeq<'_> {
+    type Ok = Expr;
+    type Error = SerializeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Expr, SerializeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeSeq<'_> {
+    type Ok = Expr;
+    type Error = SerializeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Expr, SerializeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeSeq<'_> {
+    type Ok = Expr;
+    type Error = SerializeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Expr, SerializeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeSeq<'_> {
+    type Ok = Expr;
+    type Error = SerializeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Expr, SerializeError> {
+        self.finish()
+    }
+}
+
+/// Collects the entries of a map.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct SerializeMap<'a> {
+    ser: &'a Serializer,
+    entries: Vec<(Expr, Expr)>,
+    key: Option<Expr>,
+}
+
+impl ser::SerializeMap for SerializeMap<'_> {
+    type Ok = Expr;
+    type Error = SerializeError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.key = Some(key.serialize(self.ser)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self.key.take()
+            .ok_or_else(|| ser::Error::custom("map value serialized before its key"))?;
+
+        self.entries.push((key, value.serialize(self.ser)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Expr, SerializeError> {
+        Ok(self.ser.map(self.entries))
+    }
+}
+
+/// Collects the fields of a struct or struct variant.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct SerializeStruct<'a> {
+    ser: &'a Serializer,
+    path: String,
+    fields: Vec<(String, Expr)>,
+    skipped: bool,
+}
+
+impl<'a> SerializeStruct<'a> {
+    fn new(ser: &'a Serializer, path: String, len: usize) -> Self {
+        SerializeStruct {
+            ser,
+            path,
+            fields: Vec::with_capacity(len),
+            skipped: false,
+        }
+    }
+
+    fn push<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.fields.push((key.to_string(), value.serialize(self.ser)?));
+        Ok(())
+    }
+
+    /// Returns the struct literal, filling in skipped fields with their
+    /// defaults.
+    fn finish(self) -> Result<Expr, SerializeError> {
+        let rest = self.skipped.then(|| Box::new(Expr::verbatim("Default::default()")));
+
+        Ok(Expr::Struct {
+            path: self.path,
+            fields: self.fields,
+            rest,
+        })
+    }
+}
+
+impl ser::SerializeStruct for SerializeStruct<'_> {
+    type Ok = Expr;
+    type Error = SerializeError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(key, value)
+    }
+
+    fn skip_field(&mut self, _key: &'static str) -> Result<(), SerializeError> {
+        self.skipped = true;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Expr, SerializeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeStruct<'_> {
+    type Ok = Expr;
+    type Error = SerializeError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(key, value)
+    }
+
+    fn skip_field(&mut self, _key: &'static str) -> Result<(), SerializeError> {
+        self.skipped = true;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Expr, SerializeError> {
+        self.finish()
+    }
+}
+
+impl SerializeError {
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl Error for SerializeError {}
+
+impl ser::Error for SerializeError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        SerializeError { msg: msg.to_string() }
+    }
+}
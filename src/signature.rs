@@ -0,0 +1,302 @@
+use std::fmt::{self, Write};
+
+use crate::arg::{Arg, Receiver};
+use crate::bounds::Bounds;
+use crate::formatter::Formatter;
+use crate::generics::Generics;
+use crate::pat::Pat;
+use crate::type_def::Type;
+use crate::visit::{Text, Visit};
+
+use crate::impl_macros::{
+    impl_bounds_methods,
+    impl_generic_methods,
+};
+
+
+/// Defines a function signature: everything about a function except for its
+/// docs, attributes, visibility and body.
+///
+/// A signature can be taken from a trait function and used to make the
+/// matching function of an impl, so that the two can't drift apart.
+///
+/// ```
+/// use codegen::{Function, Signature};
+///
+/// let mut sig = Signature::new("area");
+/// sig.arg_ref_self().ret("f64");
+///
+/// let mut func = Function::from_signature(sig.clone());
+/// func.line("self.w * self.h");
+///
+/// assert_eq!(func.signature(), &sig);
+/// assert_eq!(sig.to_string(), "fn area(&self) -> f64");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    /// Name of the function
+    name: String,
+    /// Function generics
+    generics: Generics,
+    /// The `self` argument, if the function takes one
+    receiver: Option<Receiver>,
+    /// Function arguments
+    args: Vec<Arg>,
+    /// Return type
+    ret: Option<Type>,
+    /// Where bounds
+    bounds: Bounds,
+    /// Function `extern` ABI
+    extern_abi: Option<String>,
+    /// Whether or not this function is `const`
+    is_const: bool,
+    /// Whether or not this function is `async` or not
+    is_async: bool,
+    /// Whether or not this function is `unsafe`
+    is_unsafe: bool,
+    /// Whether or not this function takes C-style variadic arguments
+    is_variadic: bool,
+}
+
+impl Signature {
+    /// Return a new signature of a function without arguments.
+    pub fn new<S>(name: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        Signature {
+            name: name.as_ref().to_owned(),
+            generics: Generics::default(),
+            receiver: None,
+            args: vec![],
+            ret: None,
+            bounds: Bounds::default(),
+            extern_abi: None,
+            is_const: false,
+            is_async: false,
+            is_unsafe: false,
+            is_variadic: false,
+        }
+    }
+
+    /// Set whether this function is async or not
+    pub fn set_async(&mut self, is_async: bool) -> &mut Self {
+        self.is_async = is_async;
+        self
+    }
+
+    /// Set whether this function is `const` or not.
+    pub fn set_const(&mut self, is_const: bool) -> &mut Self {
+        self.is_const = is_const;
+        self
+    }
+
+    /// Set whether this function is `unsafe` or not.
+    pub fn set_unsafe(&mut self, is_unsafe: bool) -> &mut Self {
+        self.is_unsafe = is_unsafe;
+        self
+    }
+
+    /// Set whether this function takes C-style variadic arguments (`...`).
+    /// Only foreign functions may be variadic.
+    pub fn set_variadic(&mut self, is_variadic: bool) -> &mut Self {
+        self.is_variadic = is_variadic;
+        self
+    }
+
+    /// Specify an `extern` ABI for the function.
+    pub fn extern_abi(&mut self, abi: &str) -> &mut Self {
+        self.extern_abi.replace(abi.to_string());
+        self
+    }
+
+    /// Add `self` as a function argument.
+    pub fn arg_self(&mut self) -> &mut Self {
+        self.receiver(Receiver::Value { mutable: false })
+    }
+
+    /// Add `&self` as a function argument.
+    pub fn arg_ref_self(&mut self) -> &mut Self {
+        self.receiver(Receiver::Ref { lifetime: None, mutable: false })
+    }
+
+    /// Add `&mut self` as a function argument.
+    pub fn arg_mut_self(&mut self) -> &mut Self {
+        self.receiver(Receiver::Ref { lifetime: None, mutable: true })
+    }
+
+    /// Set the `self` argument, like `&'a self` or `self: Box<Self>`.
+    pub fn receiver(&mut self, receiver: Receiver) -> &mut Self {
+        self.receiver = Some(receiver);
+        self
+    }
+
+    /// Returns the `self` argument, if any.
+    pub fn get_receiver(&self) -> Option<&Receiver> {
+        self.receiver.as_ref()
+    }
+
+    /// Add a function argument, binding a name or pattern like `(a, b)`.
+    pub fn arg<P, T>(&mut self, pat: P, ty: T) -> &mut Self
+    where
+        P: Into<Pat>,
+        T: Into<Type>,
+    {
+        self.push_arg(Arg::new(pat, ty))
+    }
+
+    /// Push a new function argument, returning a mutable reference to it so
+    /// that attributes can be added.
+    pub fn new_arg<P, T>(&mut self, pat: P, ty: T) -> &mut Arg
+    where
+        P: Into<Pat>,
+        T: Into<Type>,
+    {
+        self.push_arg(Arg::new(pat, ty));
+        self.args.last_mut().unwrap()
+    }
+
+    /// Push a function argument.
+    pub fn push_arg(&mut self, arg: Arg) -> &mut Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// Returns the function arguments, not including `self`.
+    pub fn args(&self) -> &[Arg] {
+        &self.args
+    }
+
+    /// Set the function return type.
+    pub fn ret<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        self.ret = Some(ty.into());
+        self
+    }
+
+    /// Returns the return type, if any.
+    pub fn get_ret(&self) -> Option<&Type> {
+        self.ret.as_ref()
+    }
+
+    /// Returns the name of the function.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the generics of the function.
+    pub(crate) fn generics(&self) -> &Generics {
+        &self.generics
+    }
+
+    /// Formats the signature using the given formatter, from the qualifiers
+    /// up to the `where` clause.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_const {
+            write!(fmt, "const ")?;
+        }
+
+        if self.is_async {
+            write!(fmt, "async ")?;
+        }
+
+        if self.is_unsafe {
+            write!(fmt, "unsafe ")?;
+        }
+
+        if let Some(ref extern_abi) = self.extern_abi {
+            write!(fmt, "extern \"{extern_abi}\" ", extern_abi = extern_abi)?;
+        }
+
+        write!(fmt, "fn {}", self.name)?;
+        self.generics.fmt_generics(fmt)?;
+
+        write!(fmt, "(")?;
+
+        if let Some(ref receiver) = self.receiver {
+            receiver.fmt(fmt)?;
+        }
+
+        for (i, arg) in self.args.iter().enumerate() {
+            if i != 0 || self.receiver.is_some() {
+                write!(fmt, ", ")?;
+            }
+
+            arg.fmt(fmt)?;
+        }
+
+        if self.is_variadic {
+            if self.receiver.is_some() || !self.args.is_empty() {
+                write!(fmt, ", ")?;
+            }
+
+            write!(fmt, "...")?;
+        }
+
+        write!(fmt, ")")?;
+
+        if let Some(ref ret) = self.ret {
+            write!(fmt, " -> ")?;
+            ret.fmt(fmt)?;
+        }
+
+        self.bounds.fmt_bounds(fmt)
+    }
+
+    impl_bounds_methods!(bounds);
+    impl_generic_methods!(generics);
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        Signature::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
+
+impl Visit for Signature {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.generics.visit_types(f);
+
+        if let Some(receiver) = &self.receiver {
+            receiver.visit_types(f);
+        }
+
+        for arg in &self.args {
+            arg.visit_types(f);
+        }
+
+        if let Some(ret) = &self.ret {
+            f(ret);
+        }
+
+        self.bounds.visit_types(f);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.generics.visit_types_mut(f);
+
+        if let Some(receiver) = &mut self.receiver {
+            receiver.visit_types_mut(f);
+        }
+
+        for arg in &mut self.args {
+            arg.visit_types_mut(f);
+        }
+
+        if let Some(ret) = &mut self.ret {
+            f(ret);
+        }
+
+        self.bounds.visit_types_mut(f);
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        for arg in &self.args {
+            arg.visit_text(f);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::{self, Write};
+
+use crate::attributes::Attributes;
+use crate::docs::Docs;
+use crate::error::CodegenError;
+use crate::expr::Expr;
+use crate::formatter::Formatter;
+use crate::validate::{Diagnostic, check_name};
+use crate::visit::{Text, Visit};
+use crate::type_def::Type;
+use crate::vis::Vis;
+
+use crate::impl_macros::{
+    impl_attr_methods,
+    impl_doc_methods,
+    impl_ty_methods,
+    impl_vis_methods,
+};
+
+
+/// Defines a `static` or `static mut` item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Static {
+    /// Name of the static
+    name: String,
+    /// Static documentation
+    docs: Docs,
+    /// Static attributes
+    attrs: Attributes,
+    /// Static visibility
+    vis: Vis,
+    /// Whether or not this is a `static mut`
+    is_mut: bool,
+    /// Static type
+    ty: Type,
+    /// Initializer expression
+    value: Option<Expr>,
+}
+
+impl Static {
+    /// Return a new static definition with the given name and type.
+    pub fn new<S, T>(name: S, ty: T) -> Self
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        Static {
+            name: name.as_ref().to_owned(),
+            docs: Docs::default(),
+            attrs: Attributes::default(),
+            vis: Vis::default(),
+            is_mut: false,
+            ty: ty.into(),
+            value: None,
+        }
+    }
+
+    /// Returns the name of the static.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Set whether this is a `static mut` or not.
+    pub fn set_mut(&mut self, is_mut: bool) -> &mut Self {
+        self.is_mut = is_mut;
+        self
+    }
+
+    /// Whether or not this is a `static mut`.
+    pub fn is_mut(&self) -> bool {
+        self.is_mut
+    }
+
+    /// Set the initializer expression of the static.
+    pub fn value<T>(&mut self, value: T) -> &mut Self
+    where
+        T: Into<Expr>,
+    {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Returns the initializer expression, if one is set.
+    pub fn get_value(&self) -> Option<&Expr> {
+        self.value.as_ref()
+    }
+
+    /// Reports problems with the static's name.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        check_name(&self.name, path, diags);
+    }
+
+    /// Checks that the static can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        match self.value {
+            Some(_) => Ok(()),
+            None => Err(CodegenError::MissingValue { path: self.name.clone() }),
+        }
+    }
+
+    /// Checks that the static can be formatted as a foreign static.
+    pub(crate) fn check_foreign(&self) -> Result<(), CodegenError> {
+        match self.value {
+            Some(_) => Err(CodegenError::ForeignStaticValue { path: self.name.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    /// Formats the static using the given formatter.
+    ///
+    /// Returns an error if the static has no value. [`Scope::try_to_string`]
+    /// reports it.
+    ///
+    /// [`Scope::try_to_string`]: crate::Scope::try_to_string
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let value = self.value.as_ref().ok_or(fmt::Error)?;
+
+        self.fmt_head(fmt)?;
+        write!(fmt, " = ")?;
+        value.fmt(fmt)?;
+        writeln!(fmt, ";")
+    }
+
+    /// Formats the static as a foreign static declaration in an `extern`
+    /// block.
+    pub(crate) fn fmt_foreign(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.check_foreign().map_err(|_| fmt::Error)?;
+
+        self.fmt_head(fmt)?;
+        writeln!(fmt, ";")
+    }
+
+    /// Formats everything up to the initializer expression.
+    fn fmt_head(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.docs.fmt_docs(fmt)?;
+        self.attrs.fmt_attrs(fmt)?;
+        self.vis.fmt(fmt)?;
+
+        write!(fmt, "static ")?;
+
+        if self.is_mut {
+            write!(fmt, "mut ")?;
+        }
+
+        write!(fmt, "{}: ", self.name)?;
+        self.ty.fmt(fmt)
+    }
+
+    impl_attr_methods!(attrs);
+    impl_doc_methods!(docs);
+    impl_ty_methods!(field => ty);
+    impl_vis_methods!(field => vis);
+}
+
+impl Visit for Static {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        f(&self.ty);
+
+        if let Some(value) = &self.value {
+            value.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        f(&mut self.ty);
+
+        if let Some(value) = &mut self.value {
+            value.visit_types_mut(f);
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+
+        if let Some(value) = &self.value {
+            value.visit_text(f);
+        }
+    }
+}
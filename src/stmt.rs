@@ -0,0 +1,251 @@
+use std::fmt::{self, Write};
+
+use crate::block::Block;
+use crate::expr::Expr;
+use crate::formatter::Formatter;
+use crate::item::Item;
+use crate::pat::Pat;
+use crate::type_def::Type;
+use crate::visit::{Text, Visit};
+
+
+/// A statement in a function body or block.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    /// A line of source text that is written as is.
+    Verbatim(String),
+    /// A `let` binding, like `let x: u8 = 1;` or `let x;`.
+    Let {
+        /// The bound pattern.
+        pat: Pat,
+        /// The type annotation, if any.
+        ty: Option<Type>,
+        /// The initializer, if any.
+        init: Option<Expr>,
+    },
+    /// A `let .. else` binding, like `let Some(x) = y else { return; };`.
+    LetElse {
+        /// The bound pattern.
+        pat: Pat,
+        /// The type annotation, if any.
+        ty: Option<Type>,
+        /// The initializer.
+        init: Expr,
+        /// The block run when the pattern doesn't match.
+        else_block: Block,
+    },
+    /// An expression without a trailing semicolon, like the tail of a block
+    /// or an `if` used as a statement.
+    Expr(Expr),
+    /// An expression followed by a semicolon.
+    Semi(Expr),
+    /// A nested item, like a helper `fn` or `struct`.
+    Item(Item),
+}
+
+impl Stmt {
+    /// Returns a `let pat = init;` binding.
+    pub fn let_binding<P, E>(pat: P, init: E) -> Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        Stmt::Let {
+            pat: pat.into(),
+            ty: None,
+            init: Some(init.into()),
+        }
+    }
+
+    /// Returns a `let pat = init else { .. };` binding.
+    pub fn let_else<P, E>(pat: P, init: E, else_block: Block) -> Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        Stmt::LetElse {
+            pat: pat.into(),
+            ty: None,
+            init: init.into(),
+            else_block,
+        }
+    }
+
+    /// Returns an expression statement without a trailing semicolon.
+    pub fn expr<E>(expr: E) -> Self
+    where
+        E: Into<Expr>,
+    {
+        Stmt::Expr(expr.into())
+    }
+
+    /// Returns an expression statement followed by a semicolon.
+    pub fn semi<E>(expr: E) -> Self
+    where
+        E: Into<Expr>,
+    {
+        Stmt::Semi(expr.into())
+    }
+
+    /// Returns a nested item.
+    pub fn item<I>(item: I) -> Self
+    where
+        I: Into<Item>,
+    {
+        Stmt::Item(item.into())
+    }
+
+    /// Formats the statement using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Stmt::Verbatim(line) => writeln!(fmt, "{}", line),
+            Stmt::Let { pat, ty, init } => {
+                fmt_let_head(pat, ty.as_ref(), fmt)?;
+
+                if let Some(init) = init {
+                    write!(fmt, " = ")?;
+                    init.fmt(fmt)?;
+                }
+
+                writeln!(fmt, ";")
+            }
+            Stmt::LetElse { pat, ty, init, else_block } => {
+                fmt_let_head(pat, ty.as_ref(), fmt)?;
+                write!(fmt, " = ")?;
+                init.fmt_let_else_init(fmt)?;
+                write!(fmt, " else ")?;
+                else_block.fmt_braces(fmt)?;
+                writeln!(fmt, ";")
+            }
+            Stmt::Expr(expr) => {
+                expr.fmt(fmt)?;
+                writeln!(fmt)
+            }
+            Stmt::Semi(expr) => {
+                expr.fmt(fmt)?;
+                writeln!(fmt, ";")
+            }
+            Stmt::Item(item) => item.fmt(fmt),
+        }
+    }
+
+    /// Whether the statement holds any source text that is written as is.
+    pub(crate) fn has_verbatim(&self) -> bool {
+        match self {
+            Stmt::Verbatim(_) => true,
+            Stmt::Let { init, .. } => init.as_ref().is_some_and(Expr::has_verbatim),
+            Stmt::LetElse { init, else_block, .. } => {
+                init.has_verbatim() || else_block.has_verbatim()
+            }
+            Stmt::Expr(expr) | Stmt::Semi(expr) => expr.has_verbatim(),
+            Stmt::Item(item) => {
+                let mut found = false;
+                item.visit_text(&mut |kind, _| found |= kind == Text::Verbatim);
+                found
+            }
+        }
+    }
+}
+
+impl From<Item> for Stmt {
+    fn from(src: Item) -> Self {
+        Stmt::Item(src)
+    }
+}
+
+impl Visit for Stmt {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        match self {
+            Stmt::Verbatim(_) => {}
+            Stmt::Let { ty, init, .. } => {
+                if let Some(ty) = ty {
+                    f(ty);
+                }
+                if let Some(init) = init {
+                    init.visit_types(f);
+                }
+            }
+            Stmt::LetElse { ty, init, else_block, .. } => {
+                if let Some(ty) = ty {
+                    f(ty);
+                }
+                init.visit_types(f);
+                else_block.visit_types(f);
+            }
+            Stmt::Expr(expr) | Stmt::Semi(expr) => expr.visit_types(f),
+            Stmt::Item(item) => item.visit_types(f),
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        match self {
+            Stmt::Verbatim(_) => {}
+            Stmt::Let { ty, init, .. } => {
+                if let Some(ty) = ty {
+                    f(ty);
+                }
+                if let Some(init) = init {
+                    init.visit_types_mut(f);
+                }
+            }
+            Stmt::LetElse { ty, init, else_block, .. } => {
+                if let Some(ty) = ty {
+                    f(ty);
+                }
+                init.visit_types_mut(f);
+                else_block.visit_types_mut(f);
+            }
+            Stmt::Expr(expr) | Stmt::Semi(expr) => expr.visit_types_mut(f),
+            Stmt::Item(item) => item.visit_types_mut(f),
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        match self {
+            Stmt::Verbatim(line) => f(Text::Verbatim, line),
+            Stmt::Let { pat, init, .. } => {
+                f(Text::Body, &pat.to_string());
+                if let Some(init) = init {
+                    init.visit_text(f);
+                }
+            }
+            Stmt::LetElse { pat, init, else_block, .. } => {
+                f(Text::Body, &pat.to_string());
+                init.visit_text(f);
+                else_block.visit_text(f);
+            }
+            Stmt::Expr(expr) | Stmt::Semi(expr) => expr.visit_text(f),
+            Stmt::Item(item) => item.visit_text(f),
+        }
+    }
+
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        match self {
+            Stmt::Verbatim(_) => {}
+            Stmt::Let { init, .. } => {
+                if let Some(init) = init {
+                    init.visit_items(f);
+                }
+            }
+            Stmt::LetElse { init, else_block, .. } => {
+                init.visit_items(f);
+                else_block.visit_items(f);
+            }
+            Stmt::Expr(expr) | Stmt::Semi(expr) => expr.visit_items(f),
+            Stmt::Item(item) => f(item),
+        }
+    }
+}
+
+/// Formats the `let pat: ty` part of a binding.
+fn fmt_let_head(pat: &Pat, ty: Option<&Type>, fmt: &mut Formatter<'_>) -> fmt::Result {
+    write!(fmt, "let ")?;
+    pat.fmt(fmt)?;
+
+    if let Some(ty) = ty {
+        write!(fmt, ": ")?;
+        ty.fmt(fmt)?;
+    }
+
+    Ok(())
+}
@@ -1,13 +1,17 @@
 use std::fmt::{self, Write};
 
+use crate::error::CodegenError;
 use crate::field::Field;
 use crate::fields::Fields;
 use crate::formatter::Formatter;
+use crate::ident::Edition;
+use crate::validate::Diagnostic;
+use crate::visit::{Text, Visit};
 use crate::type_def::{Type, TypeDef, impl_type_def_passthrough};
 
 
 /// Defines a struct.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Struct {
     type_def: TypeDef,
 
@@ -28,35 +32,99 @@ impl Struct {
     ///
     /// A struct can either set named fields with this function or tuple fields
     /// with `push_tuple_field`, but not both.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct already has tuple fields.
     pub fn push_field(&mut self, field: Field) -> &mut Self {
-        self.fields.push_named(field);
-        self
+        self.try_push_field(field).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Push a named field to the struct, or return an error if the struct
+    /// already has tuple fields.
+    pub fn try_push_field(&mut self, field: Field) -> Result<&mut Self, CodegenError> {
+        match self.fields.try_push_named(field) {
+            Ok(_) => Ok(self),
+            Err(err) => Err(err.in_item(self.type_def.name())),
+        }
     }
 
     /// Add a named field to the struct.
     ///
     /// A struct can either set named fields with this function or tuple fields
     /// with `tuple_field`, but not both.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct already has tuple fields.
     pub fn field<T>(&mut self, name: &str, ty: T) -> &mut Self
     where
         T: Into<Type>,
     {
-        self.fields.named(name, ty);
-        self
+        self.push_field(Field::new_named(name, ty))
+    }
+
+    /// Add a named field to the struct, or return an error if the struct
+    /// already has tuple fields.
+    pub fn try_field<T>(&mut self, name: &str, ty: T) -> Result<&mut Self, CodegenError>
+    where
+        T: Into<Type>,
+    {
+        self.try_push_field(Field::new_named(name, ty))
     }
 
     /// Add a tuple field to the struct.
     ///
     /// A struct can either set tuple fields with this function or named fields
     /// with `field`, but not both.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct already has named fields.
     pub fn tuple_field<T>(&mut self, ty: T) -> &mut Self
     where
         T: Into<Type>,
     {
-        self.fields.tuple(ty);
+        self.try_tuple_field(ty).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Add a tuple field to the struct, or return an error if the struct
+    /// already has named fields.
+    pub fn try_tuple_field<T>(&mut self, ty: T) -> Result<&mut Self, CodegenError>
+    where
+        T: Into<Type>,
+    {
+        match self.fields.try_tuple(ty) {
+            Ok(_) => Ok(self),
+            Err(err) => Err(err.in_item(self.type_def.name())),
+        }
+    }
+
+    /// Maps the names of the struct's fields to valid identifiers, escaping
+    /// keywords of the given edition. See [`Ident::sanitize`].
+    ///
+    /// [`Ident::sanitize`]: crate::Ident::sanitize
+    pub fn sanitize_names(&mut self, edition: Edition) -> &mut Self {
+        self.fields.sanitize_names(edition);
         self
     }
 
+    /// Returns the names of the struct's named fields.
+    pub(crate) fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.names()
+    }
+
+    /// Reports problems with the struct and its fields.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        self.type_def.validate(path, diags);
+        self.fields.validate(path, diags);
+    }
+
+    /// Checks that the struct can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        self.fields.check().map_err(|err| err.in_item(self.type_def.name()))
+    }
+
     /// Formats the struct using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.type_def.fmt_head("struct", &[], fmt)?;
@@ -64,10 +132,10 @@ impl Struct {
 
         match self.fields {
             Fields::Empty => {
-                write!(fmt, ";\n")?;
+                writeln!(fmt, ";")?;
             }
             Fields::Tuple(..) => {
-                write!(fmt, ";\n")?;
+                writeln!(fmt, ";")?;
             }
             _ => {}
         }
@@ -78,3 +146,20 @@ impl Struct {
     // Implement all the methods that TypeDef provides, but made to conform to the builder pattern 
     impl_type_def_passthrough!(type_def);
 }
+
+impl Visit for Struct {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.type_def.visit_types(f);
+        self.fields.visit_types(f);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.type_def.visit_types_mut(f);
+        self.fields.visit_types_mut(f);
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.type_def.visit_text(f);
+        self.fields.visit_text(f);
+    }
+}
@@ -1,16 +1,24 @@
 use std::fmt::{self, Write};
 
 use crate::associated_type::AssociatedType;
+use crate::const_gen::Const;
+use crate::error::CodegenError;
+use crate::expr::{Delimiter, Expr};
 use crate::formatter::Formatter;
+use crate::generics::GenericParam;
+use crate::impl_gen::{Impl, validate_assoc_items};
+use crate::validate::Diagnostic;
+use crate::visit::{Text, Visit};
 use crate::function::Function;
-use crate::type_def::{Type, TypeDef, impl_type_def_passthrough};
+use crate::type_def::{Type, TypeBound, TypeDef, impl_type_def_passthrough};
 
 /// Define a trait.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Trait {
     type_def: TypeDef,
-    parents: Vec<Type>,
+    parents: Vec<TypeBound>,
     associated_tys: Vec<AssociatedType>,
+    consts: Vec<Const>,
     fns: Vec<Function>,
 }
 
@@ -24,20 +32,20 @@ impl Trait {
             type_def: TypeDef::new(name),
             parents: vec![],
             associated_tys: vec![],
+            consts: vec![],
             fns: vec![],
         }
     }
 
-    /// Add a parent trait.
+    /// Add a parent trait, or a lifetime bound like `'static`.
     pub fn parent<T>(&mut self, ty: T) -> &mut Self
     where
-        T: Into<Type>,
+        T: Into<TypeBound>,
     {
         self.parents.push(ty.into());
         self
     }
 
-
     /// Add an associated type. Returns a mutable reference to the new
     /// associated type for futher configuration.
     pub fn associated_type(&mut self, name: &str) -> &mut AssociatedType {
@@ -45,6 +53,24 @@ impl Trait {
         self.associated_tys.last_mut().unwrap()
     }
 
+    /// Add an associated constant. Returns a mutable reference to the new
+    /// constant for futher configuration. A value set on the constant is used
+    /// as its default.
+    pub fn new_const<S, T>(&mut self, name: S, ty: T) -> &mut Const
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.push_const(Const::new(name, ty));
+        self.consts.last_mut().unwrap()
+    }
+
+    /// Push an associated constant.
+    pub fn push_const(&mut self, item: Const) -> &mut Self {
+        self.consts.push(item);
+        self
+    }
+
     /// Push a new function definition, returning a mutable reference to it.
     pub fn new_fn(&mut self, name: &str) -> &mut Function {
         self.push_fn(Function::new_trait_fn(name));
@@ -57,6 +83,111 @@ impl Trait {
         self
     }
 
+    /// Returns an impl of the trait for `target`, with every item that the
+    /// impl has to define.
+    ///
+    /// Associated types are set to `todo!()`, and constants and functions
+    /// without a default get a `todo!()` value or body. A `todo!()` type does
+    /// not compile, so each associated type has to be replaced with
+    /// [`Impl::associate_type`], or given up front with
+    /// [`Trait::impl_for_with`]. The generic parameters of the trait are
+    /// declared on the impl and passed to the trait.
+    ///
+    /// ```
+    /// use codegen::Trait;
+    ///
+    /// let mut shape = Trait::new("Shape");
+    /// shape.associated_type("Unit");
+    /// shape.new_fn("area").arg_ref_self().ret("f64");
+    ///
+    /// let mut imp = shape.impl_for("Square");
+    /// imp.associate_type("Unit", "f64");
+    /// ```
+    pub fn impl_for<T>(&self, target: T) -> Impl
+    where
+        T: Into<Type>,
+    {
+        self.impl_for_with(target, |_| Type::verbatim("todo!()"), |func| {
+            func.push_expr(Expr::macro_call("todo", Delimiter::Paren, None::<Expr>));
+        })
+    }
+
+    /// Returns an impl of the trait for `target` like [`Trait::impl_for`],
+    /// but calls `assoc_ty` with the name of each associated type to get its
+    /// type, and `body` to fill in each function, which starts out with the
+    /// signature from the trait and an empty body.
+    ///
+    /// ```
+    /// use codegen::Trait;
+    ///
+    /// let mut shape = Trait::new("Shape");
+    /// shape.associated_type("Unit");
+    /// shape.new_fn("area").arg_ref_self().ret("f64");
+    ///
+    /// let imp = shape.impl_for_with("Square", |_| "f64".into(), |func| {
+    ///     func.line("self.side * self.side");
+    /// });
+    /// ```
+    pub fn impl_for_with<T, A, F>(&self, target: T, mut assoc_ty: A, mut body: F) -> Impl
+    where
+        T: Into<Type>,
+        A: FnMut(&str) -> Type,
+        F: FnMut(&mut Function),
+    {
+        let params = self.type_def.generics().params();
+
+        let mut impl_trait = Type::new(self.type_def.name());
+        for param in params {
+            match param {
+                GenericParam::Lifetime { name, .. } => impl_trait.push_lifetime(name),
+                _ => impl_trait.push_generic(param.name()),
+            };
+        }
+
+        let mut imp = Impl::new(target);
+        imp.impl_trait(impl_trait)
+            .extend_generics(params.iter().map(GenericParam::without_default))
+            .extend_bounds(self.type_def.where_bounds().predicates());
+
+        for item in &self.associated_tys {
+            imp.associate_type(item.name(), assoc_ty(item.name()));
+        }
+
+        for item in self.consts.iter().filter(|item| item.get_value().is_none()) {
+            imp.new_const(item.name(), item.ty().clone())
+                .value(Expr::macro_call("todo", Delimiter::Paren, None::<Expr>));
+        }
+
+        for func in self.fns.iter().filter(|func| func.stmts().is_none()) {
+            let mut func = Function::from_signature(func.signature().clone());
+            body(&mut func);
+            imp.push_fn(func);
+        }
+
+        imp
+    }
+
+    /// Reports problems with the trait and its items.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        self.type_def.validate(path, diags);
+        validate_assoc_items(&self.consts, &self.fns, path, diags);
+    }
+
+    /// Checks that the trait can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        let name = self.type_def.name();
+
+        for item in &self.consts {
+            item.check(true).map_err(|err| err.in_item(name))?;
+        }
+
+        for func in &self.fns {
+            func.check(true).map_err(|err| err.in_item(name))?;
+        }
+
+        Ok(())
+    }
+
     /// Formats the scope using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.type_def.fmt_head("trait", &self.parents, fmt)?;
@@ -71,9 +202,13 @@ impl Trait {
                 }
             }
 
+            for constant in &self.consts {
+                constant.fmt(true, fmt)?;
+            }
+
             for (i, func) in self.fns.iter().enumerate() {
-                if i != 0 || !assoc.is_empty() {
-                    write!(fmt, "\n")?;
+                if i != 0 || !assoc.is_empty() || !self.consts.is_empty() {
+                    writeln!(fmt)?;
                 }
 
                 func.fmt(true, fmt)?;
@@ -85,3 +220,55 @@ impl Trait {
 
     impl_type_def_passthrough!(type_def);
 }
+
+impl Visit for Trait {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.type_def.visit_types(f);
+        for parent in &self.parents {
+            parent.visit_types(f);
+        }
+
+        for assoc_ty in &self.associated_tys {
+            assoc_ty.visit_types(f);
+        }
+
+        for item in &self.consts {
+            item.visit_types(f);
+        }
+
+        for func in &self.fns {
+            func.visit_types(f);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.type_def.visit_types_mut(f);
+        for parent in &mut self.parents {
+            parent.visit_types_mut(f);
+        }
+
+        for assoc_ty in &mut self.associated_tys {
+            assoc_ty.visit_types_mut(f);
+        }
+
+        for item in &mut self.consts {
+            item.visit_types_mut(f);
+        }
+
+        for func in &mut self.fns {
+            func.visit_types_mut(f);
+        }
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.type_def.visit_text(f);
+
+        for item in &self.consts {
+            item.visit_text(f);
+        }
+
+        for func in &self.fns {
+            func.visit_text(f);
+        }
+    }
+}
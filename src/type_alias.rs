@@ -0,0 +1,73 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::Formatter;
+use crate::validate::Diagnostic;
+use crate::visit::{Text, Visit};
+use crate::type_def::{Type, TypeDef, impl_type_def_passthrough};
+
+
+/// Defines a type alias, e.g. `type Result<T> = std::result::Result<T, Error>;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAlias {
+    type_def: TypeDef,
+
+    /// The aliased type
+    target: Type,
+}
+
+impl TypeAlias {
+    /// Return a type alias definition with the provided name, aliasing the
+    /// given type.
+    pub fn new<S, T>(name: S, target: T) -> Self
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        TypeAlias {
+            type_def: TypeDef::new(name),
+            target: target.into(),
+        }
+    }
+
+    /// Returns the aliased type.
+    pub fn target(&self) -> &Type {
+        &self.target
+    }
+
+    /// Returns the aliased type mutably.
+    pub fn target_mut(&mut self) -> &mut Type {
+        &mut self.target
+    }
+
+    /// Reports problems with the type alias.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        self.type_def.validate(path, diags);
+    }
+
+    /// Formats the type alias using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.type_def.fmt_head("type", &[], fmt)?;
+
+        write!(fmt, " = ")?;
+        self.target.fmt(fmt)?;
+        writeln!(fmt, ";")
+    }
+
+    impl_type_def_passthrough!(type_def);
+}
+
+impl Visit for TypeAlias {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.type_def.visit_types(f);
+        f(&self.target);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.type_def.visit_types_mut(f);
+        f(&mut self.target);
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.type_def.visit_text(f);
+    }
+}
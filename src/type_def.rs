@@ -1,10 +1,15 @@
 use std::fmt::{self, Write};
+use std::str::FromStr;
 
 use crate::attributes::Attributes;
 use crate::bounds::Bounds;
 use crate::docs::Docs;
+use crate::error::CodegenError;
 use crate::formatter::Formatter;
 use crate::generics::Generics;
+use crate::validate::{Diagnostic, check_derives, check_duplicates, check_name};
+use crate::visit::{Text, Visit};
+use crate::parse::{Parser, TypeParseError};
 use crate::vis::Vis;
 
 
@@ -19,56 +24,569 @@ use crate::impl_macros::{
 
 
 /// Defines a type.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Type {
-    name: String,
-    docs: Docs,
-    generics: Generics,
+///
+/// Types are modeled as a tree, so that generated code can be inspected before
+/// it is formatted, e.g. to check if a field is an `Option<T>`. Most of the
+/// API accepts anything that is `Into<Type>`, including plain strings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    /// A path to a type, e.g. `std::collections::HashMap<K, V>`.
+    Path(TypePath),
+    /// A reference, e.g. `&'a mut T`.
+    Ref {
+        /// Optional lifetime of the reference.
+        lifetime: Option<String>,
+        /// Whether or not this is a `&mut` reference.
+        mutable: bool,
+        /// The referenced type.
+        inner: Box<Type>,
+    },
+    /// A raw pointer, e.g. `*const T` or `*mut T`.
+    Ptr {
+        /// Whether or not this is a `*mut` pointer.
+        mutable: bool,
+        /// The pointed to type.
+        inner: Box<Type>,
+    },
+    /// A slice, e.g. `[T]`.
+    Slice(Box<Type>),
+    /// A fixed size array, e.g. `[T; 4]`. The length is written out verbatim.
+    Array(Box<Type>, String),
+    /// A tuple, e.g. `(A, B)`. An empty tuple is the unit type `()`.
+    Tuple(Vec<Type>),
+    /// A function pointer, e.g. `unsafe extern "C" fn(u8) -> u8`.
+    FnPtr(FnPtr),
+    /// A trait object, e.g. `dyn Error + Send + 'static`.
+    TraitObject(Vec<TypeBound>),
+    /// An anonymous type implementing some traits, e.g. `impl Iterator<Item = u8>`.
+    ImplTrait(Vec<TypeBound>),
+    /// A qualified path, e.g. `<T as Trait>::Output`.
+    QSelf {
+        /// The type on the left of `as`.
+        self_ty: Box<Type>,
+        /// The trait on the right of `as`, if any.
+        trait_: Option<TypePath>,
+        /// The segments following the qualified self type.
+        path: Vec<PathSegment>,
+    },
+    /// The never type, `!`.
+    Never,
+    /// The inferred type, `_`.
+    Infer,
+    /// A type written out verbatim.
+    Verbatim(String),
 }
 
 impl Type {
     /// Return a new type with the given name.
+    ///
+    /// The name is parsed with [`Type::parse`]. If it doesn't parse, it is
+    /// kept verbatim as a [`Type::Verbatim`], which [`Scope::validate`]
+    /// reports. See [`Type::try_new`] for a version that fails instead.
+    ///
+    /// [`Scope::validate`]: crate::Scope::validate
     pub fn new<S>(name: S) -> Self
     where
         S: AsRef<str>
     {
-        Type {
-            name: name.as_ref().to_owned(),
-            docs: Docs::default(),
-            generics: Generics::default(),
+        let name = name.as_ref();
+        Type::parse(name).unwrap_or_else(|_| Type::verbatim(name))
+    }
+
+    /// Return a new type with the given name, or an error if it doesn't
+    /// parse.
+    ///
+    /// ```
+    /// use codegen::{CodegenError, Type};
+    ///
+    /// assert!(Type::try_new("Vec<u8>").is_ok());
+    /// assert!(matches!(Type::try_new("Vec<u8"), Err(CodegenError::InvalidType { .. })));
+    /// ```
+    pub fn try_new<S>(name: S) -> Result<Self, CodegenError>
+    where
+        S: AsRef<str>
+    {
+        Type::parse(name.as_ref())
+            .map_err(|error| CodegenError::InvalidType { path: String::new(), error })
+    }
+
+    /// Return a type that is written out as is, without being parsed.
+    ///
+    /// [`Scope::validate`] can't tell these apart from the strings that
+    /// [`Type::new`] failed to parse, so it still warns if `src` isn't a
+    /// type it understands, like a macro call.
+    ///
+    /// [`Scope::validate`]: crate::Scope::validate
+    pub fn verbatim<S>(src: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        Type::Verbatim(src.as_ref().to_owned())
+    }
+
+    /// Parses a type from a string, e.g. `HashMap<String, Vec<u8>>`.
+    ///
+    /// ```
+    /// use codegen::Type;
+    ///
+    /// let ty = Type::parse("Option<&'a [u8]>").unwrap();
+    ///
+    /// assert!(ty.is_option());
+    /// assert!(Type::parse("Vec<u8").is_err());
+    /// ```
+    pub fn parse(src: &str) -> Result<Type, TypeParseError> {
+        let mut parser = Parser::new(src)?;
+        let ty = parser.parse_type()?;
+        parser.finish()?;
+        Ok(ty)
+    }
+
+    /// Returns a new reference to the given type.
+    pub fn new_ref<T>(inner: T) -> Self
+    where
+        T: Into<Type>
+    {
+        Type::Ref {
+            lifetime: None,
+            mutable: false,
+            inner: Box::new(inner.into()),
         }
     }
 
-    /// Returns the name of this type.
-    pub fn name(&self) -> &str {
-        self.name.as_str()
+    /// Returns a new mutable reference to the given type.
+    pub fn new_mut_ref<T>(inner: T) -> Self
+    where
+        T: Into<Type>
+    {
+        Type::Ref {
+            lifetime: None,
+            mutable: true,
+            inner: Box::new(inner.into()),
+        }
+    }
+
+    /// Returns a new slice of the given type.
+    pub fn new_slice<T>(inner: T) -> Self
+    where
+        T: Into<Type>
+    {
+        Type::Slice(Box::new(inner.into()))
+    }
+
+    /// Returns a new array of the given type and length.
+    pub fn new_array<T, S>(inner: T, len: S) -> Self
+    where
+        T: Into<Type>,
+        S: AsRef<str>,
+    {
+        Type::Array(Box::new(inner.into()), len.as_ref().to_owned())
+    }
+
+    /// Returns a new tuple made up of the given types.
+    pub fn new_tuple<I, T>(elems: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>,
+    {
+        Type::Tuple(elems.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the unit type, `()`.
+    pub fn new_unit() -> Self {
+        Type::Tuple(vec![])
+    }
+
+    /// Returns a new `dyn` trait object with the given bounds.
+    pub fn new_dyn<I, B>(bounds: I) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<TypeBound>,
+    {
+        Type::TraitObject(bounds.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns a new `impl Trait` type with the given bounds.
+    pub fn new_impl<I, B>(bounds: I) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<TypeBound>,
+    {
+        Type::ImplTrait(bounds.into_iter().map(Into::into).collect())
     }
 
-    /// Rewrite the `Type` with the provided path
+    /// Returns a new qualified path, e.g. `<T as Trait>::Name`.
+    pub fn new_qself<T, S>(self_ty: T, trait_: Option<TypePath>, name: S) -> Self
+    where
+        T: Into<Type>,
+        S: AsRef<str>,
+    {
+        Type::QSelf {
+            self_ty: Box::new(self_ty.into()),
+            trait_,
+            path: vec![PathSegment::new(name)],
+        }
+    }
+
+    /// Returns the name of this type, which is the last segment of a path.
     ///
-    /// TODO: Is this needed?
+    /// Returns `None` if this type is not a path.
+    pub fn name(&self) -> Option<&str> {
+        self.last_segment().map(PathSegment::name)
+    }
+
+    /// Rewrite the `Type` with the provided path prefixed to it.
+    #[deprecated(note = "use `Type::prefix_path` instead")]
     pub fn path(&self, path: &str) -> Type {
-        // TODO: This isn't really correct
-        assert!(!self.name.contains("::"));
+        self.prefix_path(path)
+    }
+
+    /// Rewrite the `Type` with the provided path prefixed to it.
+    ///
+    /// Types that are not paths, such as `&T` or `[T; N]`, are returned
+    /// unchanged.
+    pub fn prefix_path(&self, path: &str) -> Type {
+        match self {
+            Type::Path(ty_path) => {
+                let mut prefix = TypePath::new(path.split("::"));
+
+                prefix.segments.extend(ty_path.segments.iter().cloned());
+                Type::Path(prefix)
+            }
+            Type::Verbatim(name) => Type::Verbatim(format!("{}::{}", path, name)),
+            _ => self.clone(),
+        }
+    }
 
-        let mut name = path.to_string();
-        name.push_str("::");
-        name.push_str(&self.name);
+    /// Returns the inner path, if this type is a path.
+    pub fn as_path(&self) -> Option<&TypePath> {
+        match self {
+            Type::Path(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner path mutably, if this type is a path.
+    pub fn as_path_mut(&mut self) -> Option<&mut TypePath> {
+        match self {
+            Type::Path(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the last segment of this type's path, if it has one.
+    pub fn last_segment(&self) -> Option<&PathSegment> {
+        match self {
+            Type::Path(path) => path.last(),
+            Type::QSelf { path, .. } => path.last(),
+            _ => None,
+        }
+    }
 
-        Type {
-            name,
-            docs: self.docs.clone(),
-            generics: self.generics.clone(),
+    /// Returns the last segment of this type's path mutably, if it has one.
+    pub fn last_segment_mut(&mut self) -> Option<&mut PathSegment> {
+        match self {
+            Type::Path(path) => path.last_mut(),
+            Type::QSelf { path, .. } => path.last_mut(),
+            _ => None,
         }
     }
 
-    /// Formats the struct using the given formatter.
+    /// Checks if this is a path whose last segment has the given name, e.g.
+    /// both `Option<u8>` and `std::option::Option<u8>` are named `Option`.
+    pub fn is_named<S>(&self, name: S) -> bool
+    where
+        S: AsRef<str>
+    {
+        self.name() == Some(name.as_ref())
+    }
+
+    /// Returns the generic arguments on the last segment of this type's path.
+    pub fn generic_args(&self) -> &[GenericArg] {
+        self.last_segment()
+            .map(PathSegment::args)
+            .unwrap_or(&[])
+    }
+
+    /// Returns the type arguments on the last segment of this type's path.
+    pub fn type_args(&self) -> impl Iterator<Item = &Type> {
+        self.generic_args()
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArg::Type(ty) => Some(ty),
+                _ => None,
+            })
+    }
+
+    /// Checks if this type is an `Option<T>`.
+    pub fn is_option(&self) -> bool {
+        self.option_inner().is_some()
+    }
+
+    /// Returns `T` if this type is an `Option<T>`.
+    pub fn option_inner(&self) -> Option<&Type> {
+        if !self.is_named("Option") {
+            return None;
+        }
+
+        let mut args = self.type_args();
+
+        match (args.next(), args.next()) {
+            (Some(inner), None) => Some(inner),
+            _ => None,
+        }
+    }
+
+    /// Checks if this is the unit type, `()`.
+    pub fn is_unit(&self) -> bool {
+        matches!(self, Type::Tuple(elems) if elems.is_empty())
+    }
+
+    /// Checks if this is a reference.
+    pub fn is_ref(&self) -> bool {
+        matches!(self, Type::Ref { .. })
+    }
+
+    fn generic_segment_mut(&mut self) -> &mut PathSegment {
+        self.last_segment_mut()
+            .expect("generics can only be added to a path type")
+    }
+
+    /// Pushes a lifetime argument onto the last segment of this type's path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this type is not a path.
+    pub fn push_lifetime<S>(&mut self, lifetime: S) -> &mut Self
+    where
+        S: AsRef<str>
+    {
+        self.generic_segment_mut().push_arg(GenericArg::Lifetime(lifetime.as_ref().to_owned()));
+        self
+    }
+
+    /// Pushes many lifetime arguments onto the last segment of this type's path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this type is not a path.
+    pub fn extend_lifetimes<I, S>(&mut self, lifetimes: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>
+    {
+        for lifetime in lifetimes {
+            self.push_lifetime(lifetime);
+        }
+
+        self
+    }
+
+    /// Removes all lifetime arguments from the last segment of this type's path.
+    pub fn clear_lifetimes(&mut self) -> &mut Self {
+        if let Some(segment) = self.last_segment_mut() {
+            segment.args.retain(|arg| !matches!(arg, GenericArg::Lifetime(_)));
+        }
+
+        self
+    }
+
+    /// Pushes a type argument onto the last segment of this type's path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this type is not a path.
+    pub fn push_generic<T>(&mut self, generic: T) -> &mut Self
+    where
+        T: Into<Type>
+    {
+        self.generic_segment_mut().push_arg(GenericArg::Type(generic.into()));
+        self
+    }
+
+    /// Pushes many type arguments onto the last segment of this type's path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this type is not a path.
+    pub fn extend_generics<I, T>(&mut self, generics: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>
+    {
+        for generic in generics {
+            self.push_generic(generic);
+        }
+
+        self
+    }
+
+    /// Removes all type arguments from the last segment of this type's path.
+    pub fn clear_generics(&mut self) -> &mut Self {
+        if let Some(segment) = self.last_segment_mut() {
+            segment.args.retain(|arg| !matches!(arg, GenericArg::Type(_)));
+        }
+
+        self
+    }
+
+    /// Calls `f` on every path within the type, outermost first, including
+    /// the paths nested in generic arguments.
+    pub(crate) fn visit_paths_mut(&mut self, f: &mut dyn FnMut(&mut TypePath)) {
+        match self {
+            Type::Path(path) => path.visit_paths_mut(f),
+            Type::Ref { inner, .. }
+            | Type::Ptr { inner, .. }
+            | Type::Slice(inner)
+            | Type::Array(inner, _) => inner.visit_paths_mut(f),
+            Type::Tuple(tys) => {
+                for ty in tys {
+                    ty.visit_paths_mut(f);
+                }
+            }
+            Type::FnPtr(fn_ptr) => {
+                for arg in &mut fn_ptr.args {
+                    arg.visit_paths_mut(f);
+                }
+
+                if let Some(ret) = &mut fn_ptr.ret {
+                    ret.visit_paths_mut(f);
+                }
+            }
+            Type::TraitObject(bounds) | Type::ImplTrait(bounds) => {
+                for ty in bounds.iter_mut().filter_map(TypeBound::ty_mut) {
+                    ty.visit_paths_mut(f);
+                }
+            }
+            Type::QSelf { self_ty, trait_, path } => {
+                self_ty.visit_paths_mut(f);
+
+                if let Some(trait_) = trait_ {
+                    trait_.visit_paths_mut(f);
+                }
+
+                // The trailing segments are relative to the qualified type,
+                // so only their arguments are visited.
+                for segment in path {
+                    segment.visit_args_mut(f);
+                }
+            }
+            Type::Never | Type::Infer | Type::Verbatim(_) => {}
+        }
+    }
+
+    /// Formats the type using the given formatter.
     pub(crate) fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        write!(fmt, "{}", self.name)?;
-        self.generics.fmt_generics(fmt)
+        match self {
+            Type::Path(path) => path.fmt(fmt),
+            Type::Ref { lifetime, mutable, inner } => {
+                write!(fmt, "&")?;
+
+                if let Some(lifetime) = lifetime {
+                    write!(fmt, "{} ", lifetime)?;
+                }
+
+                if *mutable {
+                    write!(fmt, "mut ")?;
+                }
+
+                inner.fmt_pointee(fmt)
+            }
+            Type::Ptr { mutable, inner } => {
+                if *mutable {
+                    write!(fmt, "*mut ")?;
+                } else {
+                    write!(fmt, "*const ")?;
+                }
+
+                inner.fmt_pointee(fmt)
+            }
+            Type::Slice(inner) => {
+                write!(fmt, "[")?;
+                inner.fmt(fmt)?;
+                write!(fmt, "]")
+            }
+            Type::Array(inner, len) => {
+                write!(fmt, "[")?;
+                inner.fmt(fmt)?;
+                write!(fmt, "; {}]", len)
+            }
+            Type::Tuple(elems) => {
+                write!(fmt, "(")?;
+
+                for (i, elem) in elems.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, ", ")?;
+                    }
+                    elem.fmt(fmt)?;
+                }
+
+                if elems.len() == 1 {
+                    write!(fmt, ",")?;
+                }
+
+                write!(fmt, ")")
+            }
+            Type::FnPtr(fn_ptr) => fn_ptr.fmt(fmt),
+            Type::TraitObject(bounds) => {
+                write!(fmt, "dyn ")?;
+                fmt_type_bounds(bounds, fmt)
+            }
+            Type::ImplTrait(bounds) => {
+                write!(fmt, "impl ")?;
+                fmt_type_bounds(bounds, fmt)
+            }
+            Type::QSelf { self_ty, trait_, path } => {
+                write!(fmt, "<")?;
+                self_ty.fmt(fmt)?;
+
+                if let Some(trait_) = trait_ {
+                    write!(fmt, " as ")?;
+                    trait_.fmt(fmt)?;
+                }
+
+                write!(fmt, ">")?;
+
+                for segment in path {
+                    write!(fmt, "::")?;
+                    segment.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+            Type::Never => write!(fmt, "!"),
+            Type::Infer => write!(fmt, "_"),
+            Type::Verbatim(raw) => write!(fmt, "{}", raw),
+        }
     }
 
-    impl_generic_methods!(generics);
+    /// Formats a type behind a reference or pointer, wrapping trait objects
+    /// with multiple bounds in parentheses so they aren't ambiguous.
+    fn fmt_pointee(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::TraitObject(bounds) | Type::ImplTrait(bounds) if bounds.len() > 1 => {
+                write!(fmt, "(")?;
+                self.fmt(fmt)?;
+                write!(fmt, ")")
+            }
+            _ => self.fmt(fmt),
+        }
+    }
+}
+
+impl FromStr for Type {
+    type Err = TypeParseError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Type::parse(src)
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        Type::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
 }
 
 
@@ -88,6 +606,560 @@ impl<'a> From<&'a Type> for Type {
     }
 }
 
+impl From<TypePath> for Type {
+    fn from(src: TypePath) -> Self {
+        Type::Path(src)
+    }
+}
+
+impl From<FnPtr> for Type {
+    fn from(src: FnPtr) -> Self {
+        Type::FnPtr(src)
+    }
+}
+
+
+/// A path to a type, made up of `::` separated segments.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypePath {
+    global: bool,
+    segments: Vec<PathSegment>,
+}
+
+impl TypePath {
+    /// Returns a new path made up of the given segments.
+    pub fn new<I, S>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<PathSegment>,
+    {
+        TypePath {
+            global: false,
+            segments: segments.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Sets whether or not this path starts with a leading `::`.
+    pub fn set_global(&mut self, global: bool) -> &mut Self {
+        self.global = global;
+        self
+    }
+
+    /// Whether or not this path starts with a leading `::`.
+    pub fn is_global(&self) -> bool {
+        self.global
+    }
+
+    /// Returns the segments of this path.
+    pub fn segments(&self) -> &[PathSegment] {
+        self.segments.as_slice()
+    }
+
+    /// Returns the segments of this path mutably.
+    pub fn segments_mut(&mut self) -> &mut Vec<PathSegment> {
+        &mut self.segments
+    }
+
+    /// Returns the last segment of this path.
+    pub fn last(&self) -> Option<&PathSegment> {
+        self.segments.last()
+    }
+
+    /// Returns the last segment of this path mutably.
+    pub fn last_mut(&mut self) -> Option<&mut PathSegment> {
+        self.segments.last_mut()
+    }
+
+    /// Pushes a segment onto the end of this path.
+    pub fn push_segment<S>(&mut self, segment: S) -> &mut Self
+    where
+        S: Into<PathSegment>
+    {
+        self.segments.push(segment.into());
+        self
+    }
+
+    /// Calls `f` on this path, then on the paths nested in its generic
+    /// arguments.
+    pub(crate) fn visit_paths_mut(&mut self, f: &mut dyn FnMut(&mut TypePath)) {
+        f(self);
+
+        for segment in &mut self.segments {
+            segment.visit_args_mut(f);
+        }
+    }
+
+    /// Formats the path using the given formatter.
+    pub(crate) fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if self.global {
+            write!(fmt, "::")?;
+        }
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i != 0 {
+                write!(fmt, "::")?;
+            }
+            segment.fmt(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<S> From<S> for TypePath
+where
+    S: AsRef<str>
+{
+    fn from(src: S) -> Self {
+        let src = src.as_ref();
+
+        Parser::new(src)
+            .and_then(|mut parser| {
+                let path = parser.parse_path()?;
+                parser.finish()?;
+                Ok(path)
+            })
+            .unwrap_or_else(|_| TypePath::new(src.split("::")))
+    }
+}
+
+
+/// A single segment of a path, with its generic arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathSegment {
+    name: String,
+    args: Vec<GenericArg>,
+    /// Parenthesized arguments, as in `Fn(A, B) -> C`.
+    fn_inputs: Option<Vec<Type>>,
+    fn_output: Option<Box<Type>>,
+}
+
+impl PathSegment {
+    /// Returns a new path segment without any generic arguments.
+    pub fn new<S>(name: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        PathSegment {
+            name: name.as_ref().to_owned(),
+            args: vec![],
+            fn_inputs: None,
+            fn_output: None,
+        }
+    }
+
+    /// Returns the name of this segment.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the generic arguments of this segment.
+    pub fn args(&self) -> &[GenericArg] {
+        self.args.as_slice()
+    }
+
+    /// Returns the generic arguments of this segment mutably.
+    pub fn args_mut(&mut self) -> &mut Vec<GenericArg> {
+        &mut self.args
+    }
+
+    /// Pushes a generic argument onto this segment.
+    pub fn push_arg<A>(&mut self, arg: A) -> &mut Self
+    where
+        A: Into<GenericArg>
+    {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Sets parenthesized arguments on this segment, as used by the `Fn`
+    /// traits, e.g. `Fn(A, B) -> C`.
+    pub fn set_fn_sugar<I, T>(&mut self, inputs: I, output: Option<Type>) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>,
+    {
+        self.fn_inputs = Some(inputs.into_iter().map(Into::into).collect());
+        self.fn_output = output.map(Box::new);
+        self
+    }
+
+    /// Returns the parenthesized argument types, if this segment has them.
+    pub fn fn_inputs(&self) -> Option<&[Type]> {
+        self.fn_inputs.as_deref()
+    }
+
+    /// Returns the parenthesized return type, if this segment has one.
+    pub fn fn_output(&self) -> Option<&Type> {
+        self.fn_output.as_deref()
+    }
+
+    /// Calls `f` on the paths within the segment's generic arguments.
+    pub(crate) fn visit_args_mut(&mut self, f: &mut dyn FnMut(&mut TypePath)) {
+        for arg in &mut self.args {
+            if let GenericArg::Type(ty) | GenericArg::Binding(_, ty) = arg {
+                ty.visit_paths_mut(f);
+            }
+        }
+
+        for input in self.fn_inputs.iter_mut().flatten() {
+            input.visit_paths_mut(f);
+        }
+
+        if let Some(output) = &mut self.fn_output {
+            output.visit_paths_mut(f);
+        }
+    }
+
+    /// Formats the segment using the given formatter.
+    ///
+    /// Rust requires lifetimes to come first and associated type bindings to
+    /// come last, so arguments are written in that order regardless of the
+    /// order they were pushed in.
+    pub(crate) fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}", self.name)?;
+
+        if let Some(ref inputs) = self.fn_inputs {
+            write!(fmt, "(")?;
+
+            for (i, input) in inputs.iter().enumerate() {
+                if i != 0 {
+                    write!(fmt, ", ")?;
+                }
+                input.fmt(fmt)?;
+            }
+
+            write!(fmt, ")")?;
+
+            if let Some(ref output) = self.fn_output {
+                write!(fmt, " -> ")?;
+                output.fmt(fmt)?;
+            }
+
+            return Ok(());
+        }
+
+        if self.args.is_empty() {
+            return Ok(());
+        }
+
+        let mut args: Vec<&GenericArg> = self.args.iter().collect();
+        args.sort_by_key(|arg| arg.order());
+
+        write!(fmt, "<")?;
+
+        for (i, arg) in args.into_iter().enumerate() {
+            if i != 0 {
+                write!(fmt, ", ")?;
+            }
+            arg.fmt(fmt)?;
+        }
+
+        write!(fmt, ">")
+    }
+}
+
+impl<S> From<S> for PathSegment
+where
+    S: AsRef<str>
+{
+    fn from(src: S) -> Self {
+        PathSegment::new(src)
+    }
+}
+
+
+/// A generic argument on a path segment, e.g. the `'a`, `T` and `Item = u8` in
+/// `Foo<'a, T, Item = u8>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GenericArg {
+    /// A lifetime argument, e.g. `'a`.
+    Lifetime(String),
+    /// A type argument.
+    Type(Type),
+    /// A const argument, written out verbatim, e.g. `4` or `{ N + 1 }`.
+    Const(String),
+    /// An associated type binding, e.g. `Item = u8`.
+    Binding(String, Type),
+}
+
+impl GenericArg {
+    fn order(&self) -> u8 {
+        match self {
+            GenericArg::Lifetime(_) => 0,
+            GenericArg::Type(_) | GenericArg::Const(_) => 1,
+            GenericArg::Binding(..) => 2,
+        }
+    }
+
+    /// Formats the argument using the given formatter.
+    pub(crate) fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GenericArg::Lifetime(lifetime) => write!(fmt, "{}", lifetime),
+            GenericArg::Type(ty) => ty.fmt(fmt),
+            GenericArg::Const(value) => write!(fmt, "{}", value),
+            GenericArg::Binding(name, ty) => {
+                write!(fmt, "{} = ", name)?;
+                ty.fmt(fmt)
+            }
+        }
+    }
+}
+
+impl<T> From<T> for GenericArg
+where
+    T: Into<Type>
+{
+    fn from(src: T) -> Self {
+        GenericArg::Type(src.into())
+    }
+}
+
+
+/// A bound on a type, e.g. `Send`, `?Sized`, `for<'de> Deserialize<'de>` or
+/// `'static`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypeBound {
+    /// A trait bound.
+    Trait(Type),
+    /// A relaxed trait bound, e.g. `?Sized`.
+    Maybe(Type),
+    /// A higher-ranked trait bound, e.g. `for<'a> Fn(&'a str)`.
+    HigherRanked {
+        /// The lifetimes introduced by `for<..>`.
+        lifetimes: Vec<String>,
+        /// The trait bound.
+        ty: Type,
+    },
+    /// A lifetime bound, e.g. `'static`.
+    Lifetime(String),
+}
+
+impl TypeBound {
+    /// Returns a relaxed trait bound, e.g. `?Sized`.
+    pub fn maybe<T>(ty: T) -> Self
+    where
+        T: Into<Type>,
+    {
+        TypeBound::Maybe(ty.into())
+    }
+
+    /// Returns a higher-ranked trait bound over the given lifetimes, e.g.
+    /// `for<'de> Deserialize<'de>`.
+    pub fn higher_ranked<I, S, T>(lifetimes: I, ty: T) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+        T: Into<Type>,
+    {
+        TypeBound::HigherRanked {
+            lifetimes: lifetimes.into_iter().map(|l| l.to_string()).collect(),
+            ty: ty.into(),
+        }
+    }
+
+    /// Returns the bounding trait, if this isn't a lifetime bound.
+    pub(crate) fn ty(&self) -> Option<&Type> {
+        match self {
+            TypeBound::Trait(ty) | TypeBound::Maybe(ty) | TypeBound::HigherRanked { ty, .. } => {
+                Some(ty)
+            }
+            TypeBound::Lifetime(_) => None,
+        }
+    }
+
+    /// Returns the bounding trait mutably, if this isn't a lifetime bound.
+    pub(crate) fn ty_mut(&mut self) -> Option<&mut Type> {
+        match self {
+            TypeBound::Trait(ty) | TypeBound::Maybe(ty) | TypeBound::HigherRanked { ty, .. } => {
+                Some(ty)
+            }
+            TypeBound::Lifetime(_) => None,
+        }
+    }
+
+    /// Formats the bound using the given formatter.
+    pub(crate) fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeBound::Trait(ty) => ty.fmt(fmt),
+            TypeBound::Maybe(ty) => {
+                write!(fmt, "?")?;
+                ty.fmt(fmt)
+            }
+            TypeBound::HigherRanked { lifetimes, ty } => {
+                fmt_for_lifetimes(lifetimes, fmt)?;
+                ty.fmt(fmt)
+            }
+            TypeBound::Lifetime(lifetime) => write!(fmt, "{}", lifetime),
+        }
+    }
+}
+
+/// Writes the `for<'a, 'b> ` binder of a higher-ranked bound.
+pub(crate) fn fmt_for_lifetimes(lifetimes: &[String], fmt: &mut Formatter<'_>) -> fmt::Result {
+    write!(fmt, "for<{}> ", lifetimes.join(", "))
+}
+
+impl fmt::Display for TypeBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dst = String::new();
+        TypeBound::fmt(self, &mut Formatter::new(&mut dst))?;
+        f.write_str(&dst)
+    }
+}
+
+impl Visit for TypeBound {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        if let Some(ty) = self.ty() {
+            f(ty);
+        }
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        if let Some(ty) = self.ty_mut() {
+            f(ty);
+        }
+    }
+}
+
+impl<S> From<S> for TypeBound
+where
+    S: AsRef<str>
+{
+    fn from(src: S) -> Self {
+        let src = src.as_ref();
+
+        Parser::new(src)
+            .and_then(|mut parser| {
+                let bound = parser.parse_bound()?;
+                parser.finish()?;
+                Ok(bound)
+            })
+            .unwrap_or_else(|_| TypeBound::Trait(Type::Verbatim(src.to_owned())))
+    }
+}
+
+impl From<Type> for TypeBound {
+    fn from(src: Type) -> Self {
+        TypeBound::Trait(src)
+    }
+}
+
+fn fmt_type_bounds(bounds: &[TypeBound], fmt: &mut Formatter<'_>) -> fmt::Result {
+    for (i, bound) in bounds.iter().enumerate() {
+        if i != 0 {
+            write!(fmt, " + ")?;
+        }
+        bound.fmt(fmt)?;
+    }
+
+    Ok(())
+}
+
+
+/// A function pointer type, e.g. `unsafe extern "C" fn(u8, ...) -> u8`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct FnPtr {
+    is_unsafe: bool,
+    abi: Option<String>,
+    args: Vec<Type>,
+    variadic: bool,
+    ret: Option<Box<Type>>,
+}
+
+impl FnPtr {
+    /// Returns a new function pointer with no arguments and no return type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether or not this function pointer is `unsafe`.
+    pub fn set_unsafe(&mut self, is_unsafe: bool) -> &mut Self {
+        self.is_unsafe = is_unsafe;
+        self
+    }
+
+    /// Specify an `extern` ABI for the function pointer.
+    pub fn extern_abi<S>(&mut self, abi: S) -> &mut Self
+    where
+        S: AsRef<str>
+    {
+        self.abi = Some(abi.as_ref().to_owned());
+        self
+    }
+
+    /// Add an argument type.
+    pub fn arg<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>
+    {
+        self.args.push(ty.into());
+        self
+    }
+
+    /// Sets whether or not this function pointer takes C-style variadic
+    /// arguments.
+    pub fn set_variadic(&mut self, variadic: bool) -> &mut Self {
+        self.variadic = variadic;
+        self
+    }
+
+    /// Set the return type.
+    pub fn ret<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>
+    {
+        self.ret = Some(Box::new(ty.into()));
+        self
+    }
+
+    /// Returns the argument types.
+    pub fn args(&self) -> &[Type] {
+        self.args.as_slice()
+    }
+
+    /// Returns the return type, if any.
+    pub fn get_ret(&self) -> Option<&Type> {
+        self.ret.as_deref()
+    }
+
+    /// Formats the function pointer using the given formatter.
+    pub(crate) fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_unsafe {
+            write!(fmt, "unsafe ")?;
+        }
+
+        if let Some(ref abi) = self.abi {
+            write!(fmt, "extern \"{}\" ", abi)?;
+        }
+
+        write!(fmt, "fn(")?;
+
+        for (i, arg) in self.args.iter().enumerate() {
+            if i != 0 {
+                write!(fmt, ", ")?;
+            }
+            arg.fmt(fmt)?;
+        }
+
+        if self.variadic {
+            if !self.args.is_empty() {
+                write!(fmt, ", ")?;
+            }
+            write!(fmt, "...")?;
+        }
+
+        write!(fmt, ")")?;
+
+        if let Some(ref ret) = self.ret {
+            write!(fmt, " -> ")?;
+            ret.fmt(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
 
 macro_rules! impl_ty_methods {
     (field => $($inner:tt)+) => {
@@ -118,9 +1190,10 @@ pub(crate) use impl_ty_methods;
 
 
 /// Defines a type definition.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TypeDef {
     ty: Type,
+    generics: Generics,
     vis: Vis,
     docs: Docs,
     derive: Vec<String>,
@@ -139,6 +1212,7 @@ impl TypeDef {
     {
         TypeDef {
             ty: Type::new(name),
+            generics: Generics::default(),
             vis: Vis::Private,
             docs: Docs::default(),
             derive: vec![],
@@ -179,10 +1253,14 @@ impl TypeDef {
         self.repr = Some(repr.as_ref().to_owned());
     }
 
-    pub fn fmt_head<S, P>(&self, keyword: S, parents: P, fmt: &mut Formatter<'_>) -> fmt::Result
+    pub fn fmt_head<S>(
+        &self,
+        keyword: S,
+        parents: &[TypeBound],
+        fmt: &mut Formatter<'_>,
+    ) -> fmt::Result
     where
         S: AsRef<str>,
-        P: AsRef<[Type]>,
     {
         self.docs.fmt_docs(fmt)?;
         self.fmt_allow(fmt)?;
@@ -194,18 +1272,16 @@ impl TypeDef {
 
         write!(fmt, "{} ", keyword.as_ref())?;
         self.ty.fmt(fmt)?;
+        self.generics.fmt_generics(fmt)?;
 
-        let parents = parents.as_ref();
-        if !parents.is_empty() {
-            for (i, ty) in parents.iter().enumerate() {
-                if i == 0 {
-                    write!(fmt, ": ")?;
-                } else {
-                    write!(fmt, " + ")?;
-                }
-
-                ty.fmt(fmt)?;
+        for (i, parent) in parents.iter().enumerate() {
+            if i == 0 {
+                write!(fmt, ": ")?;
+            } else {
+                write!(fmt, " + ")?;
             }
+
+            parent.fmt(fmt)?;
         }
 
         self.bounds.fmt_bounds(fmt)?;
@@ -215,7 +1291,7 @@ impl TypeDef {
 
     fn fmt_allow(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         for allow in &self.allow {
-            write!(fmt, "#[allow({})]\n", allow)?;
+            writeln!(fmt, "#[allow({})]", allow)?;
         }
 
         Ok(())
@@ -223,12 +1299,49 @@ impl TypeDef {
 
     fn fmt_repr(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         if let Some(ref repr) = self.repr {
-            write!(fmt, "#[repr({})]\n", repr)?;
+            writeln!(fmt, "#[repr({})]", repr)?;
         }
 
         Ok(())
     }
 
+    /// Reports problems with the type's name, derives and generics.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        check_name(self.name(), path, diags);
+        check_derives(&self.derive, path, diags);
+
+        let params = self.ty.generic_args()
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArg::Lifetime(lifetime) => Some(lifetime.as_str()),
+                GenericArg::Type(ty) => ty.name(),
+                _ => None,
+            })
+            .chain(self.generics.names());
+
+        check_duplicates(params, "generic parameter", |_| path.to_owned(), diags);
+    }
+
+    /// Returns the generic parameters of the defined type.
+    pub(crate) fn generics(&self) -> &Generics {
+        &self.generics
+    }
+
+    /// Returns the `where` clause of the defined type.
+    pub(crate) fn where_bounds(&self) -> &Bounds {
+        &self.bounds
+    }
+
+    /// Whether or not a `repr` attribute is set.
+    pub(crate) fn has_repr(&self) -> bool {
+        self.repr.is_some()
+    }
+
+    /// Returns the name of the defined type, used in error paths.
+    pub(crate) fn name(&self) -> &str {
+        self.ty.name().unwrap_or_default()
+    }
+
     fn fmt_derive(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         if !self.derive.is_empty() {
             write!(fmt, "#[derive(")?;
@@ -240,7 +1353,7 @@ impl TypeDef {
                 write!(fmt, "{}", name)?;
             }
 
-            write!(fmt, ")]\n")?;
+            writeln!(fmt, ")]")?;
         }
 
         Ok(())
@@ -249,7 +1362,7 @@ impl TypeDef {
     impl_attr_methods!(attrs);
     impl_bounds_methods!(bounds);
     impl_doc_methods!(docs);
-    impl_generic_methods!(ty);
+    impl_generic_methods!(generics);
     impl_ty_methods!(field => ty);
     impl_vis_methods!(field => vis);
 }
@@ -309,3 +1422,25 @@ macro_rules! impl_type_def_passthrough {
 }
 
 pub(crate) use impl_type_def_passthrough;
+
+impl Visit for TypeDef {
+    // The defined type itself only names the type, so just the generics and
+    // bounds are visited.
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.generics.visit_types(f);
+        self.bounds.visit_types(f);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.generics.visit_types_mut(f);
+        self.bounds.visit_types_mut(f);
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        for derive in &self.derive {
+            f(Text::Attr, derive);
+        }
+
+        self.attrs.visit_text(f);
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+
+use crate::error::CodegenError;
+use crate::field::Field;
+use crate::fields::Fields;
+use crate::formatter::Formatter;
+use crate::ident::Edition;
+use crate::validate::Diagnostic;
+use crate::visit::{Text, Visit};
+use crate::type_def::{Type, TypeDef, impl_type_def_passthrough};
+
+
+/// Defines a union.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Union {
+    type_def: TypeDef,
+
+    /// Union fields. Always named.
+    fields: Fields,
+}
+
+impl Union {
+    /// Return a union definition with the provided name
+    pub fn new<S>(name: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        Union {
+            type_def: TypeDef::new(name),
+            fields: Fields::Empty,
+        }
+    }
+
+    /// Push a named field to the union.
+    pub fn push_field(&mut self, field: Field) -> &mut Self {
+        self.fields.push_named(field);
+        self
+    }
+
+    /// Add a named field to the union.
+    pub fn field<S, T>(&mut self, name: S, ty: T) -> &mut Self
+    where
+        S: AsRef<str>,
+        T: Into<Type>,
+    {
+        self.fields.named(name, ty);
+        self
+    }
+
+    /// Maps the names of the union's fields to valid identifiers, escaping
+    /// keywords of the given edition. See [`Ident::sanitize`].
+    ///
+    /// [`Ident::sanitize`]: crate::Ident::sanitize
+    pub fn sanitize_names(&mut self, edition: Edition) -> &mut Self {
+        self.fields.sanitize_names(edition);
+        self
+    }
+
+    /// Reports problems with the union and its fields.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        self.type_def.validate(path, diags);
+        self.fields.validate(path, diags);
+    }
+
+    /// Checks that the union can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        match self.fields {
+            Fields::Named(_) => self.fields.check(),
+            _ => Err(CodegenError::EmptyFields { path: String::new() }),
+        }
+        .map_err(|err| err.in_item(self.type_def.name()))
+    }
+
+    /// Formats the union using the given formatter.
+    ///
+    /// Returns an error if the union has no named fields.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.check().map_err(|_| fmt::Error)?;
+
+        self.type_def.fmt_head("union", &[], fmt)?;
+        self.fields.fmt(fmt)
+    }
+
+    impl_type_def_passthrough!(type_def);
+}
+
+impl Visit for Union {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.type_def.visit_types(f);
+        self.fields.visit_types(f);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.type_def.visit_types_mut(f);
+        self.fields.visit_types_mut(f);
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.type_def.visit_text(f);
+        self.fields.visit_text(f);
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::ident::{Ident, is_ident};
+
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The generated code may not compile.
+    Warning,
+    /// The generated code won't compile.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+
+/// A problem found by [`Scope::validate`].
+///
+/// [`Scope::validate`]: crate::Scope::validate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    severity: Severity,
+    path: String,
+    message: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn new<P, M>(severity: Severity, path: P, message: M) -> Self
+    where
+        P: Into<String>,
+        M: Into<String>,
+    {
+        Diagnostic {
+            severity,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Returns how serious the problem is.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the path of the offending item, such as `a::b::Foo.field`.
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    /// Returns a description of the problem.
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: `{}`: {}", self.severity, self.path, self.message)
+    }
+}
+
+
+/// Returns the path of an item defined in `module`.
+pub(crate) fn item_path(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}::{}", module, name)
+    }
+}
+
+/// Returns the path of a member of the item at `parent`.
+pub(crate) fn member_path(parent: &str, name: &str) -> String {
+    format!("{}.{}", parent, name)
+}
+
+/// Reports a name at `path` that isn't a valid identifier or is a reserved
+/// keyword.
+pub(crate) fn check_name(name: &str, path: &str, diags: &mut Vec<Diagnostic>) {
+    if !is_ident(name.strip_prefix("r#").unwrap_or(name)) {
+        diags.push(Diagnostic::new(
+            Severity::Error,
+            path,
+            format!("`{}` is not a valid identifier", name),
+        ));
+        return;
+    }
+
+    match Ident::new(name) {
+        Ok(ident) if !ident.is_raw() || name.starts_with("r#") => {}
+        _ => {
+            diags.push(Diagnostic::new(
+                Severity::Error,
+                path,
+                format!("`{}` is a reserved keyword", name),
+            ));
+        }
+    }
+}
+
+/// Reports every name that appears more than once, as a `kind` that is
+/// already defined.
+pub(crate) fn check_duplicates<'a, I, F>(names: I, kind: &str, path: F, diags: &mut Vec<Diagnostic>)
+where
+    I: IntoIterator<Item = &'a str>,
+    F: Fn(&str) -> String,
+{
+    let mut seen = HashSet::new();
+    let mut reported = HashSet::new();
+
+    for name in names {
+        if !seen.insert(name) && reported.insert(name) {
+            diags.push(Diagnostic::new(
+                Severity::Error,
+                path(name),
+                format!("the {} `{}` is defined multiple times", kind, name),
+            ));
+        }
+    }
+}
+
+/// Reports derives that require another trait to be derived as well, like
+/// `Copy` without `Clone`.
+///
+/// These are only warnings, since the required trait may be implemented by
+/// hand instead.
+pub(crate) fn check_derives(derives: &[String], path: &str, diags: &mut Vec<Diagnostic>) {
+    const REQUIRES: &[(&str, &[&str])] = &[
+        ("Copy", &["Clone"]),
+        ("Eq", &["PartialEq"]),
+        ("PartialOrd", &["PartialEq"]),
+        ("Ord", &["PartialOrd", "Eq"]),
+    ];
+
+    // Derives may be written as paths, like `std::clone::Clone`.
+    let derived: Vec<&str> = derives.iter()
+        .map(|derive| derive.rsplit("::").next().unwrap_or(derive).trim())
+        .collect();
+
+    for (derive, required) in REQUIRES {
+        if !derived.contains(derive) {
+            continue;
+        }
+
+        for required in required.iter().filter(|required| !derived.contains(required)) {
+            diags.push(Diagnostic::new(
+                Severity::Warning,
+                path,
+                format!("deriving `{}` requires deriving `{}`", derive, required),
+            ));
+        }
+    }
+}
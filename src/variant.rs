@@ -2,8 +2,12 @@ use std::fmt::{self, Write};
 
 use crate::attributes::Attributes;
 use crate::docs::Docs;
+use crate::error::CodegenError;
 use crate::fields::Fields;
 use crate::formatter::Formatter;
+use crate::ident::{Edition, Ident};
+use crate::validate::{Diagnostic, check_name};
+use crate::visit::{Text, Visit};
 
 use crate::type_def::Type;
 
@@ -13,12 +17,13 @@ use crate::impl_macros::{
 };
 
 /// Defines an enum variant.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Variant {
     name: String,
     docs: Docs,
     attrs: Attributes,
     fields: Fields,
+    discriminant: Option<String>,
 }
 
 impl Variant {
@@ -29,24 +34,96 @@ impl Variant {
             docs: Docs::default(),
             attrs: Attributes::default(),
             fields: Fields::Empty,
+            discriminant: None,
         }
     }
 
     /// Add a named field to the variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variant already has tuple fields.
     pub fn named<T>(&mut self, name: &str, ty: T) -> &mut Self
     where
         T: Into<Type>,
     {
-        self.fields.named(name, ty);
-        self
+        self.try_named(name, ty).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Add a named field to the variant, or return an error if the variant
+    /// already has tuple fields.
+    pub fn try_named<T>(&mut self, name: &str, ty: T) -> Result<&mut Self, CodegenError>
+    where
+        T: Into<Type>,
+    {
+        match self.fields.try_named(name, ty) {
+            Ok(_) => Ok(self),
+            Err(err) => Err(err.in_item(&self.name)),
+        }
     }
 
     /// Add a tuple field to the variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variant already has named fields.
     pub fn tuple(&mut self, ty: &str) -> &mut Self {
-        self.fields.tuple(ty);
+        self.try_tuple(ty).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Add a tuple field to the variant, or return an error if the variant
+    /// already has named fields.
+    pub fn try_tuple(&mut self, ty: &str) -> Result<&mut Self, CodegenError> {
+        match self.fields.try_tuple(ty) {
+            Ok(_) => Ok(self),
+            Err(err) => Err(err.in_item(&self.name)),
+        }
+    }
+
+    /// Returns the name of the variant.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Maps the variant's name, and the names of its fields, to valid
+    /// identifiers, escaping keywords of the given edition. See
+    /// [`Ident::sanitize`].
+    pub fn sanitize_name(&mut self, edition: Edition) -> &mut Self {
+        self.name = Ident::sanitize_with_edition(&self.name, edition).into();
+        self.fields.sanitize_names(edition);
+        self
+    }
+
+    /// Set the discriminant of the variant, i.e. `Foo = 1`.
+    pub fn discriminant<T>(&mut self, value: T) -> &mut Self
+    where
+        T: ToString,
+    {
+        self.discriminant = Some(value.to_string());
         self
     }
 
+    /// Returns the discriminant of the variant, if one is set.
+    pub fn get_discriminant(&self) -> Option<&str> {
+        self.discriminant.as_deref()
+    }
+
+    /// Whether or not the variant has no fields.
+    pub(crate) fn is_unit(&self) -> bool {
+        matches!(self.fields, Fields::Empty)
+    }
+
+    /// Reports problems with the variant and its fields.
+    pub(crate) fn validate(&self, path: &str, diags: &mut Vec<Diagnostic>) {
+        check_name(&self.name, path, diags);
+        self.fields.validate(path, diags);
+    }
+
+    /// Checks that the variant can be formatted.
+    pub(crate) fn check(&self) -> Result<(), CodegenError> {
+        self.fields.check().map_err(|err| err.in_item(&self.name))
+    }
+
     /// Formats the variant using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.docs.fmt_docs(fmt)?;
@@ -54,7 +131,12 @@ impl Variant {
 
         write!(fmt, "{}", self.name)?;
         self.fields.fmt(fmt)?;
-        write!(fmt, ",\n")?;
+
+        if let Some(ref discriminant) = self.discriminant {
+            write!(fmt, " = {}", discriminant)?;
+        }
+
+        writeln!(fmt, ",")?;
 
         Ok(())
     }
@@ -62,3 +144,18 @@ impl Variant {
     impl_attr_methods!(attrs);
     impl_doc_methods!(docs);
 }
+
+impl Visit for Variant {
+    fn visit_types(&self, f: &mut dyn FnMut(&Type)) {
+        self.fields.visit_types(f);
+    }
+
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        self.fields.visit_types_mut(f);
+    }
+
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        self.attrs.visit_text(f);
+        self.fields.visit_text(f);
+    }
+}
@@ -4,10 +4,11 @@ use crate::formatter::Formatter;
 
 
 /// Visibility levels. A variant that can handle specific paths may be added in the future.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[non_exhaustive]
 pub enum Vis {
     /// Private visibility. The default. Equivilent to having no visibility modifier.
+    #[default]
     Private,
     /// Fully public
     Pub,
@@ -17,12 +18,6 @@ pub enum Vis {
     PubSuper,
 }
 
-impl Default for Vis {
-    fn default() -> Self {
-        Self::Private
-    }
-}
-
 impl Vis {
     /// returns the raw, unformatted visibility modifier.
     pub fn vis_string(&self) -> Option<&'static str> {
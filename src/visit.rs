@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+use crate::item::Item;
+use crate::type_def::Type;
+
+
+/// The kind of source text held by an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Text {
+    /// The contents of an attribute or derive.
+    Attr,
+    /// Code built from statements and expressions, like `const` values.
+    Body,
+    /// Source text that is written as is, like function body lines.
+    Verbatim,
+}
+
+/// Traversal of the types and source text referenced by an item.
+///
+/// Used by passes that rewrite or inspect everything in a scope, such as
+/// import collection and pruning.
+pub(crate) trait Visit {
+    /// Calls `f` on every type referenced by the item.
+    fn visit_types(&self, f: &mut dyn FnMut(&Type));
+
+    /// Calls `f` on every type referenced by the item, allowing it to be
+    /// rewritten.
+    fn visit_types_mut(&mut self, f: &mut dyn FnMut(&mut Type));
+
+    /// Calls `f` on the source text held by the item that may refer to
+    /// imported names.
+    fn visit_text(&self, f: &mut dyn FnMut(Text, &str)) {
+        let _ = f;
+    }
+
+    /// Calls `f` on every item nested in the code held by the item, like a
+    /// helper `fn` defined in a function body. The items nested within those
+    /// are left to them.
+    fn visit_items(&self, f: &mut dyn FnMut(&Item)) {
+        let _ = f;
+    }
+}
+
+/// Returns the identifiers in `text` that could refer to an imported name,
+/// i.e. those that aren't preceded by `::` or `.`.
+///
+/// This is a heuristic: string literals and comments aren't skipped.
+pub(crate) fn referenced_idents(text: &str) -> Vec<&str> {
+    ident_spans(text)
+        .into_iter()
+        .filter(|span| !is_qualified(&text[..span.start]))
+        .map(|span| &text[span])
+        .collect()
+}
+
+/// Returns whether `text` could call a trait method or a macro, either of
+/// which may need an import that none of its identifiers refer to, like
+/// `use std::io::Write;` for `out.write_all(buf)`.
+///
+/// This errs on the side of `true`: any method call, call of a qualified
+/// path like `u8::from_str(s)`, or macro invocation counts.
+pub(crate) fn may_use_traits(text: &str) -> bool {
+    ident_spans(text).into_iter().any(|span| {
+        let after = text[span.end..].trim_start();
+
+        if after.starts_with('!') && !after.starts_with("!=") {
+            return true;
+        }
+
+        is_qualified(&text[..span.start]) && (after.starts_with('(') || after.starts_with("::<"))
+    })
+}
+
+/// Whether the text before an identifier ends with `::` or `.`.
+fn is_qualified(before: &str) -> bool {
+    let before = before.trim_end();
+    before.ends_with("::") || before.ends_with('.')
+}
+
+/// Returns the byte ranges of the identifiers in `text`.
+fn ident_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = vec![];
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if !(c.is_alphabetic() || c == '_') {
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+
+        while let Some(&(i, c)) = chars.peek() {
+            if !(c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+
+        spans.push(start..end);
+    }
+
+    spans
+}
@@ -12,15 +12,22 @@ where
         .args(["--edition", "2021"])
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()?;
 
-    let stdin = spawned.stdin.as_mut().expect("no stdin handle");
-    stdin.write(raw_code.as_ref().as_bytes())?;
+    let mut stdin = spawned.stdin.take().expect("no stdin handle");
+    stdin.write_all(raw_code.as_ref().as_bytes())?;
 
     drop(stdin);
 
     let output = spawned.wait_with_output()?;
 
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
 
     let formatted = String::from_utf8(output.stdout)
         .unwrap();
@@ -762,3 +769,1671 @@ enum IpAddrKind {
     assert_eq!(generated, expected);
     Ok(())
 }
+
+#[test]
+fn struct_with_structured_types() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let mut callback = FnPtr::new();
+    callback.set_unsafe(true)
+        .extern_abi("C")
+        .arg(Type::Ptr { mutable: true, inner: Box::new("u8".into()) })
+        .set_variadic(true)
+        .ret("i32");
+
+    scope.new_struct("Foo")
+        .push_lifetime("'a")
+        .push_generic("T")
+        .field("name", Type::Ref {
+            lifetime: Some("'a".to_string()),
+            mutable: false,
+            inner: Box::new("str".into()),
+        })
+        .field("bytes", Type::new_mut_ref(Type::new_slice("u8")))
+        .field("buf", Type::new_array("u8", "16"))
+        .field("pair", Type::new_tuple(["u8", "u16"]))
+        .field("single", Type::new_tuple(["u8"]))
+        .field("unit", Type::new_unit())
+        .field("err", Type::new_ref(Type::new_dyn(["std::error::Error", "Send", "'a"])))
+        .field("output", Type::new_qself("T", Some("Iterator".into()), "Item"))
+        .field("callback", callback);
+
+    let expected = format_code(r#"
+struct Foo<'a, T> {
+    name: &'a str,
+    bytes: &mut [u8],
+    buf: [u8; 16],
+    pair: (u8, u16),
+    single: (u8,),
+    unit: (),
+    err: &(dyn std::error::Error + Send + 'a),
+    output: <T as Iterator>::Item,
+    callback: unsafe extern "C" fn(*mut u8, ...) -> i32,
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn type_inspection() {
+    let mut option = Type::new("std::option::Option");
+    option.push_generic("u8");
+
+    assert!(option.is_option());
+    assert!(option.is_named("Option"));
+    assert_eq!(option.option_inner(), Some(&Type::new("u8")));
+    assert_eq!(option.to_string(), "std::option::Option<u8>");
+
+    let mut map = Type::new("HashMap");
+    map.push_generic("K").push_lifetime("'a");
+
+    assert!(!map.is_option());
+    assert_eq!(map.name(), Some("HashMap"));
+    assert_eq!(map.to_string(), "HashMap<'a, K>");
+
+    assert!(Type::new_unit().is_unit());
+    assert!(Type::new_ref("str").is_ref());
+    assert_eq!(Type::new_ref("str").name(), None);
+    assert_eq!(Type::new("Foo").prefix_path("crate::bar").to_string(), "crate::bar::Foo");
+    assert_eq!(Type::new_ref("Foo").prefix_path("crate::bar"), Type::new_ref("Foo"));
+    assert!(Type::new_unit().as_path().is_none());
+}
+
+#[test]
+fn parse_type_round_trip() {
+    let cases = [
+        "HashMap<String, Vec<u8>>",
+        "&'static str",
+        "&mut [u8]",
+        "[u8; 4 * N]",
+        "(u8, (u16,), ())",
+        "*const c_void",
+        "std::collections::HashMap<K, V>",
+        "::std::io::Result<()>",
+        "Box<dyn Fn(u8, u16) -> u32 + Send + 'static>",
+        "impl Iterator<Item = &'a T>",
+        "<T as IntoIterator>::IntoIter",
+        "<Self>::Output",
+        "unsafe extern \"C\" fn(i32, ...) -> i32",
+        "Array<u8, 3>",
+        "Cow<'a, str>",
+        "!",
+        "_",
+    ];
+
+    for case in cases {
+        let ty = Type::parse(case).unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(ty.to_string(), case);
+        assert_eq!(case.parse::<Type>(), Ok(ty));
+    }
+
+    assert_eq!(Type::parse("Vec::<u8>").unwrap().to_string(), "Vec<u8>");
+    assert_eq!(Type::parse("(u8)").unwrap(), Type::new("u8"));
+}
+
+#[test]
+fn parse_type_structure() {
+    let ty = Type::parse("std::collections::HashMap<String, Vec<Option<u8>>>").unwrap();
+
+    let path = ty.as_path().unwrap();
+    assert_eq!(path.segments().len(), 3);
+    assert_eq!(ty.name(), Some("HashMap"));
+
+    let value = ty.type_args().nth(1).unwrap();
+    assert!(value.is_named("Vec"));
+    assert!(value.type_args().next().unwrap().is_option());
+
+    match Type::parse("&'a mut T").unwrap() {
+        Type::Ref { lifetime, mutable, inner } => {
+            assert_eq!(lifetime.as_deref(), Some("'a"));
+            assert!(mutable);
+            assert_eq!(*inner, Type::new("T"));
+        }
+        other => panic!("expected a reference, found {:?}", other),
+    }
+}
+
+#[test]
+fn parse_type_errors() {
+    let cases = [
+        "Vec<u8", "HashMap<String,, u8>", "[u8; ]", "&", "Foo Bar", "T: Clone", "*u8", "&' u8",
+        r#"extern "C fn()"#,
+    ];
+
+    for case in cases {
+        let err = Type::parse(case).unwrap_err();
+        assert_eq!(err.src(), case);
+    }
+
+    let err = Type::parse("Vec<u8").unwrap_err();
+    assert_eq!(err.offset(), 6);
+    assert_eq!(err.to_string(), "expected `>`, found end of input at offset 6 in `Vec<u8`");
+
+    let err = Type::parse("&' u8").unwrap_err();
+    assert_eq!(err.to_string(), "expected a lifetime name at offset 1 in `&' u8`");
+
+    // Character literals aren't lifetimes, and strings can hold escaped quotes.
+    for case in ["Foo<'a'>", r"Foo<'\''>", r#"extern "a\"b" fn()"#] {
+        assert_eq!(Type::try_new(case).unwrap().to_string(), case);
+    }
+
+    // Strings that don't parse are still accepted verbatim.
+    assert_eq!(Type::from("T: Clone"), Type::Verbatim("T: Clone".to_string()));
+}
+
+#[test]
+fn invalid_types() {
+    let err = Type::try_new("Vec<u8").unwrap_err();
+
+    assert_eq!(err, CodegenError::InvalidType {
+        path: String::new(),
+        error: Type::parse("Vec<u8").unwrap_err(),
+    });
+    assert_eq!(
+        err.to_string(),
+        "types must be valid: expected `>`, found end of input at offset 6 in `Vec<u8`",
+    );
+    assert_eq!(Type::try_new("Vec<u8>"), Ok(Type::new("Vec<u8>")));
+
+    let mut scope = Scope::new();
+
+    scope.new_struct("Foo")
+        .field("one", "Vec<u8")
+        .field("two", Type::verbatim("my_macro!()"));
+
+    scope.new_fn("run")
+        .arg("x", "HashMap<String,, u8>")
+        .line("todo!()");
+
+    let diags: Vec<String> = scope.validate().iter().map(ToString::to_string).collect();
+
+    assert_eq!(diags, [
+        "warning: `Foo`: `Vec<u8` is not a valid type, so it is written as is",
+        "warning: `Foo`: `my_macro!()` is not a valid type, so it is written as is",
+        "warning: `run`: `HashMap<String,, u8>` is not a valid type, so it is written as is",
+    ]);
+}
+
+#[test]
+fn consts_and_statics() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.new_const("VERSION", "&str")
+        .set_vis(Vis::Pub)
+        .push_doc("The crate version.")
+        .value("\"1.0.0\"");
+
+    scope.new_static("TABLE", "[u8; 3]")
+        .push_attr("#[no_mangle]")
+        .value("[1, 2, 3]");
+
+    scope.new_static("COUNTER", "usize")
+        .set_mut(true)
+        .set_vis(Vis::PubCrate)
+        .value("0");
+
+    scope.new_module("inner")
+        .new_const("MAX", "u32")
+        .value("u32::MAX");
+
+    let expected = format_code(r#"
+/// The crate version.
+pub const VERSION: &str = "1.0.0";
+
+#[no_mangle]
+static TABLE: [u8; 3] = [1, 2, 3];
+
+pub(crate) static mut COUNTER: usize = 0;
+
+mod inner {
+    const MAX: u32 = u32::MAX;
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn associated_consts() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let trt = scope.new_trait("Shape");
+    trt.new_const("SIDES", "u32");
+    trt.new_const("NAME", "&'static str").value("\"shape\"");
+    trt.new_fn("area").arg_ref_self().ret("f64");
+
+    let imp = scope.new_impl("Square");
+    imp.impl_trait("Shape");
+    imp.new_const("SIDES", "u32").value("4");
+    imp.new_fn("area")
+        .arg_ref_self()
+        .ret("f64")
+        .line("self.0 * self.0");
+
+    let expected = format_code(r#"
+trait Shape {
+    const SIDES: u32;
+    const NAME: &'static str = "shape";
+
+    fn area(&self) -> f64;
+}
+
+impl Shape for Square {
+    const SIDES: u32 = 4;
+
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn type_aliases() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let module = scope.new_module("error");
+
+    module.new_type_alias("Result", "std::result::Result<T, Error>")
+        .set_vis(Vis::Pub)
+        .push_doc("A result with this crate's error type.")
+        .push_generic("T");
+
+    module.push_type_alias({
+        let mut alias = TypeAlias::new("Callback", "Box<dyn Fn(T)>");
+        alias.push_generic("T").push_bound(Bound::new_with_bound("T", "Clone"));
+        alias
+    });
+
+    let expected = format_code(r#"
+mod error {
+    /// A result with this crate's error type.
+    pub type Result<T> = std::result::Result<T, Error>;
+
+    type Callback<T>
+    where
+        T: Clone,
+    = Box<dyn Fn(T)>;
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn repr_c_union() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.new_union("Value")
+        .repr("C")
+        .derive_many(["Clone", "Copy"])
+        .set_vis(Vis::Pub)
+        .field("int", "i64")
+        .field("float", "f64")
+        .push_field({
+            let mut field = Field::new_named("ptr", "*mut c_void");
+            field.push_doc("An owned pointer.");
+            field
+        });
+
+    scope.new_module("ffi")
+        .new_union("Pair")
+        .field("wide", "u32")
+        .field("narrow", "[u16; 2]");
+
+    let expected = format_code(r#"
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union Value {
+    int: i64,
+    float: f64,
+    /// An owned pointer.
+    ptr: *mut c_void,
+}
+
+mod ffi {
+    union Pair {
+        wide: u32,
+        narrow: [u16; 2],
+    }
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn extern_block() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let block = scope.new_extern_block("C");
+    block.link("foo");
+
+    block.new_fn("foo")
+        .arg("x", "i32")
+        .arg("y", "*const u8")
+        .ret("i32");
+
+    block.new_fn("printf")
+        .set_vis(Vis::Pub)
+        .arg("fmt", "*const c_char")
+        .set_variadic(true)
+        .ret("c_int");
+
+    block.new_static("BAR", "u32");
+    block.new_static("errno", "c_int").set_mut(true);
+
+    scope.new_fn("call_foo")
+        .extern_abi("C")
+        .arg("a", "i32")
+        .arg("b", "i32")
+        .ret("i32")
+        .line("unsafe { foo(a, b as *const u8) }");
+
+    let expected = format_code(r#"
+#[link(name = "foo")]
+extern "C" {
+    fn foo(x: i32, y: *const u8) -> i32;
+    pub fn printf(fmt: *const c_char, ...) -> c_int;
+    static BAR: u32;
+    static mut errno: c_int;
+}
+
+extern "C" fn call_foo(a: i32, b: i32) -> i32 {
+    unsafe { foo(a, b as *const u8) }
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn unsafe_extern_block() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let mut block = ExternBlock::new_default_abi();
+    block.set_unsafe(true);
+    block.new_fn("abort");
+    scope.push_extern_block(block);
+
+    let expected = format_code(r#"
+unsafe extern {
+    fn abort();
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn use_trees() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.import_tree("std::io::{self, Read}");
+    scope.import_tree(UseTree::rename("std::fmt::Result", "FmtResult"));
+    scope.import_tree(UseTree::glob("std::io::prelude"));
+    scope.import_tree("std::collections::{HashMap, hash_map::Entry}");
+    scope.import_tree(UseTree::group("std::io", ["Write"]));
+    scope.import_tree("std::io::Read");
+    scope.import_tree("serde::de");
+    scope.import_tree("serde::de::{self}");
+    scope.import_tree("foo::{self, bar::{A, B}}");
+    scope.import_tree("foo::bar::A");
+
+    assert_eq!(scope.to_string(), r#"use std::io::{self, Read, Write};
+use std::fmt::Result as FmtResult;
+use std::io::prelude::*;
+use std::collections::{HashMap, hash_map::Entry};
+use serde::de;
+use foo::{self, bar::{A, B}};
+"#);
+    Ok(())
+}
+
+#[test]
+fn use_tree_structure() {
+    assert_eq!(
+        UseTree::from("a::{self, b::C as D, *}"),
+        UseTree::Path("a".into(), Box::new(UseTree::Group(vec![
+            UseTree::Name("self".into()),
+            UseTree::Path("b".into(), Box::new(UseTree::Rename("C".into(), "D".into()))),
+            UseTree::Glob,
+        ]))),
+    );
+    assert_eq!(UseTree::from("a::b::C"), UseTree::name("a::b::C"));
+    assert_eq!(UseTree::from("a::b::C as D").imported_name(), Some("D"));
+    assert_eq!(UseTree::from("a::*").imported_name(), None);
+}
+
+#[test]
+fn reexports_and_extern_crates() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.extern_crate("alloc");
+    scope.extern_crate("self").alias("my_crate").set_vis(Vis::Pub);
+    scope.import_tree("crate::inner::Foo");
+    scope.import_tree("crate::inner::Bar").set_vis(Vis::Pub);
+    scope.import_tree("crate::inner::Baz").set_vis(Vis::Pub);
+
+    let expected = format_code(r#"
+extern crate alloc;
+pub extern crate self as my_crate;
+use crate::inner::Foo;
+pub use crate::inner::{Bar, Baz};
+
+struct Foo;"#)?;
+
+    scope.new_struct("Foo");
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn import_order_alphabetical() {
+    let mut scope = Scope::new();
+
+    scope.set_import_order(ImportOrder::Alphabetical);
+    scope.import_tree("std::io::{Write, self, Read}");
+    scope.import_tree("crate::foo::Foo");
+    scope.import_tree("serde");
+    scope.import("std::collections", "HashMap");
+    scope.import("bar", "Bar").set_vis(Vis::Pub);
+    scope.import("alloc::vec", "Vec").set_vis(Vis::Pub);
+
+    assert_eq!(scope.to_string(), r#"use crate::foo::Foo;
+use serde;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+pub use alloc::vec::Vec;
+pub use bar::Bar;
+"#);
+}
+
+#[test]
+fn import_order_grouped() {
+    let mut scope = Scope::new();
+
+    scope.extern_crate("log");
+    scope.extern_crate("alloc");
+    scope.set_import_order(ImportOrder::Grouped);
+    scope.import("super", "Parent");
+    scope.import("serde", "Serialize");
+    scope.import("core::fmt", "Debug");
+    scope.import("crate::foo", "Foo");
+    scope.import("std::sync", "Arc");
+    scope.import("indexmap", "IndexMap");
+    scope.import("crate::foo", "Bar").set_vis(Vis::Pub);
+    scope.import("serde", "Deserialize").set_vis(Vis::Pub);
+    scope.new_module("inner")
+        .set_import_order(ImportOrder::Grouped)
+        .import("self::a", "A")
+        .import("std::rc", "Rc")
+        .new_struct("Inner");
+
+    assert_eq!(scope.to_string(), r#"extern crate alloc;
+extern crate log;
+use core::fmt::Debug;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde::Serialize;
+pub use serde::Deserialize;
+
+use crate::foo::Foo;
+use super::Parent;
+pub use crate::foo::Bar;
+
+mod inner {
+    use std::rc::Rc;
+
+    use self::a::A;
+
+    struct Inner;
+}"#);
+}
+
+#[test]
+fn collect_imports() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.import("std::fmt", "Display");
+
+    scope.new_struct("Foo")
+        .push_generic("T")
+        .push_bound(Bound::new_with_bound("T", "std::fmt::Display"))
+        .field("map", "std::collections::HashMap<String, std::sync::Arc<T>>")
+        .field("io", "std::io::Result<()>")
+        .field("fmt", "std::fmt::Result")
+        .field("res", "Result<(), crate::error::Error>")
+        .field("assoc", "<T as std::iter::IntoIterator>::Item");
+
+    scope.new_struct("Error");
+
+    scope.new_fn("run")
+        .arg("input", "&dyn std::io::Read")
+        .ret("::serde_json::Value")
+        .line("todo!()");
+
+    scope.new_module("inner")
+        .new_struct("Bar")
+        .field("map", "std::collections::HashMap<u8, u8>");
+
+    scope.collect_imports();
+
+    let expected = format_code(r#"
+use std::fmt::Display;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::io::Read;
+use ::serde_json::Value;
+
+struct Foo<T>
+where
+    T: Display,
+{
+    map: HashMap<String, Arc<T>>,
+    io: std::io::Result<()>,
+    fmt: std::fmt::Result,
+    res: Result<(), crate::error::Error>,
+    assoc: <T as IntoIterator>::Item,
+}
+
+struct Error;
+
+fn run(input: &dyn Read) -> Value {
+    todo!()
+}
+
+mod inner {
+    use std::collections::HashMap;
+
+    struct Bar {
+        map: HashMap<u8, u8>,
+    }
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn collect_imports_prelude() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.new_fn("write")
+        .ret("std::fmt::Result")
+        .arg("out", "std::vec::Vec<std::string::String>")
+        .arg("opt", "std::option::Option<std::rc::Rc<u8>>")
+        .line("let res: Result<(), ()> = Ok(());")
+        .line("todo!()");
+
+    scope.collect_imports();
+
+    let expected = format_code(r#"
+use std::rc::Rc;
+
+fn write(out: Vec<String>, opt: Option<Rc<u8>>) -> std::fmt::Result {
+    let res: Result<(), ()> = Ok(());
+    todo!()
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn prune_imports() {
+    let mut scope = Scope::new();
+
+    scope.import_tree("std::collections::{HashMap, HashSet, BTreeMap}");
+    scope.import_tree("std::fmt::{self, Debug}");
+    scope.import_tree("serde::{Serialize, Deserialize}");
+    scope.import_tree("std::io::prelude::*");
+    scope.import_tree("std::io::Write as _");
+    scope.import_tree("std::sync::Arc");
+    scope.import_tree("std::rc::Rc");
+    scope.import_tree("crate::limits::MAX");
+    scope.import_tree("crate::unused::Reexported").set_vis(Vis::Pub);
+
+    scope.new_struct("Foo")
+        .derive("Serialize")
+        .field("map", "HashMap<String, Vec<fmt::Result>>");
+
+    scope.new_module("inner")
+        .import("std::rc", "Rc")
+        .new_struct("Bar")
+        .field("rc", "Rc<u8>");
+
+    let mut types_only = scope.clone();
+    types_only.prune_imports(false);
+
+    assert_eq!(types_only.to_string(), r#"use std::collections::HashMap;
+use std::fmt::{self};
+use serde::Serialize;
+use std::io::prelude::*;
+use std::io::Write as _;
+pub use crate::unused::Reexported;
+
+#[derive(Serialize)]
+struct Foo {
+    map: HashMap<String, Vec<fmt::Result>>,
+}
+
+mod inner {
+    use std::rc::Rc;
+
+    struct Bar {
+        rc: Rc<u8>,
+    }
+}"#);
+
+    scope.new_fn("limit")
+        .ret("Arc<HashSet<u8>>")
+        .push_expr(Expr::path("MAX"));
+
+    // The body isn't scanned, so it could use any of the imports.
+    let mut without_bodies = scope.clone();
+    without_bodies.prune_imports(false);
+    assert!(without_bodies.to_string().starts_with(r#"use std::collections::{HashMap, HashSet, BTreeMap};
+use std::fmt::{self, Debug};
+use serde::{Serialize, Deserialize};
+"#));
+
+    scope.prune_imports(true);
+
+    assert!(scope.to_string().starts_with(r#"use std::collections::{HashMap, HashSet};
+use std::fmt::{self};
+use serde::Serialize;
+use std::io::prelude::*;
+use std::io::Write as _;
+use std::sync::Arc;
+use crate::limits::MAX;
+pub use crate::unused::Reexported;
+"#));
+}
+
+#[test]
+fn prune_imports_keeps_traits() {
+    let mut scope = Scope::new();
+
+    scope.import_tree("std::io::{self, Write}");
+    scope.import_tree("std::rc::Rc");
+
+    scope.new_fn("flush")
+        .arg("out", "&mut Vec<u8>")
+        .ret("io::Result<()>")
+        .push_expr(Expr::path("out").method("write_all", [Expr::lit(Lit::byte_str("done"))]));
+
+    let mut structured = scope.clone();
+    structured.prune_imports(true);
+    assert!(structured.to_string().starts_with("use std::io::{self, Write};\nuse std::rc::Rc;\n"));
+
+    let mut verbatim = Scope::new();
+    verbatim.import_tree("std::fmt::Write");
+    verbatim.new_fn("show")
+        .arg("out", "&mut String")
+        .line("let _ = writeln!(out);");
+
+    verbatim.prune_imports(true);
+    assert!(verbatim.to_string().starts_with("use std::fmt::Write;\n"));
+}
+
+#[test]
+fn fallible_builders() {
+    let mut scope = Scope::new();
+
+    let module = scope.new_module("a");
+    module.new_struct("Foo").field("one", "u8");
+
+    let err = module.new_struct("Bar")
+        .tuple_field("u8")
+        .try_field("two", "u8")
+        .unwrap_err();
+
+    assert_eq!(err, CodegenError::MixedFields { path: "Bar.two".to_string() });
+    assert_eq!(err.to_string(), "`Bar.two`: named and tuple fields can not be mixed");
+
+    let err = module.new_enum("Baz")
+        .new_variant("Qux")
+        .named("three", "u8")
+        .try_tuple("u8")
+        .unwrap_err();
+
+    assert_eq!(err.path(), "Qux");
+
+    assert!(module.try_new_module("b").is_ok());
+    assert_eq!(
+        module.try_new_module("b").unwrap_err(),
+        CodegenError::DuplicateModule { path: "a::b".to_string() },
+    );
+    assert_eq!(
+        scope.try_push_module(Module::new("a")).unwrap_err(),
+        CodegenError::DuplicateModule { path: "a".to_string() },
+    );
+}
+
+#[test]
+fn try_to_string() {
+    let mut scope = Scope::new();
+
+    scope.new_struct("Foo").field("one", "u8");
+    assert_eq!(scope.try_to_string(), Ok(scope.to_string()));
+
+    scope.new_module("a")
+        .new_module("b")
+        .new_impl("Foo")
+        .push_fn(Function::new_trait_fn("bar"));
+
+    assert_eq!(
+        scope.try_to_string(),
+        Err(CodegenError::MissingFnBody { path: "a::b::Foo.bar".to_string() }),
+    );
+
+    let mut scope = Scope::new();
+
+    scope.new_trait("Foo")
+        .new_fn("bar")
+        .set_vis(Vis::Pub);
+
+    assert_eq!(
+        scope.try_to_string(),
+        Err(CodegenError::TraitItemVis { path: "Foo.bar".to_string() }),
+    );
+
+    let mut scope = Scope::new();
+
+    scope.new_union("Foo");
+
+    assert_eq!(
+        scope.try_to_string(),
+        Err(CodegenError::EmptyFields { path: "Foo".to_string() }),
+    );
+}
+
+#[test]
+fn invalid_items_fail_to_format() {
+    let mut dst = String::new();
+    let mut fmt = Formatter::new(&mut dst);
+
+    let mut func = Function::new("bar");
+    func.set_vis(Vis::Pub);
+    assert!(func.fmt(true, &mut fmt).is_err());
+    assert!(Function::new_trait_fn("bar").fmt(false, &mut fmt).is_err());
+    assert!(Const::new("MAX", "u8").fmt(false, &mut fmt).is_err());
+    assert!(Static::new("MAX", "u8").fmt(&mut fmt).is_err());
+    assert!(Union::new("Foo").fmt(&mut fmt).is_err());
+
+    let mut scope = Scope::new();
+
+    scope.new_extern_block("C")
+        .new_fn("foo")
+        .line("loop {}");
+
+    assert_eq!(
+        scope.try_to_string(),
+        Err(CodegenError::ForeignFnBody { path: "foo".to_string() }),
+    );
+
+    let mut scope = Scope::new();
+
+    scope.new_static("COUNTER", "usize");
+
+    assert_eq!(
+        scope.try_to_string(),
+        Err(CodegenError::MissingValue { path: "COUNTER".to_string() }),
+    );
+}
+
+#[test]
+fn validate_scope() {
+    let mut scope = Scope::new();
+
+    scope.new_struct("Foo")
+        .derive("Copy")
+        .push_generic("T")
+        .push_generic("T")
+        .field("one", "u8")
+        .field("one", "u16")
+        .field("type", "u8")
+        .field("r#type", "u8")
+        .field("2x", "u8");
+
+    scope.new_struct("Foo");
+    scope.new_fn("Foo");
+
+    let module = scope.new_module("a");
+    let en = module.new_enum("Bar");
+    en.new_variant("A").discriminant(1);
+    en.new_variant("B").tuple("u8");
+    en.new_variant("A");
+
+    module.new_enum("Baz")
+        .repr("u8")
+        .push_variant(Variant::new("A").discriminant(1).named("x", "u8").clone());
+
+    module.new_const("lower", "u8").value(Expr::lit(0));
+    module.new_impl("Bar")
+        .new_fn("run")
+        .push_lifetime("'a")
+        .push_lifetime("'a");
+
+    let diags: Vec<String> = scope.validate().iter().map(ToString::to_string).collect();
+
+    assert_eq!(diags, [
+        "error: `Foo`: the name `Foo` is defined multiple times",
+        "warning: `Foo`: deriving `Copy` requires deriving `Clone`",
+        "error: `Foo`: the generic parameter `T` is defined multiple times",
+        "error: `Foo.type`: `type` is a reserved keyword",
+        "error: `Foo.2x`: `2x` is not a valid identifier",
+        "error: `Foo.one`: the field `one` is defined multiple times",
+        "error: `a::Bar.A`: the variant `A` is defined multiple times",
+        "error: `a::Bar`: discriminants on enums with non-unit variants require a `repr`",
+        "error: `a::Bar.run`: the generic parameter `'a` is defined multiple times",
+    ]);
+    assert_eq!(scope.validate()[0].severity(), Severity::Error);
+    assert_eq!(scope.validate()[0].path(), "Foo");
+}
+
+#[test]
+fn nested_item_checks() {
+    let mut scope = Scope::new();
+
+    let mut helper = Struct::new("Helper");
+    helper.field("type", "u8").field("one", "u8").field("one", "u16");
+
+    let mut cond = If::new("ready");
+    cond.body().push_item(helper);
+
+    scope.new_module("a")
+        .new_fn("run")
+        .push_expr(cond)
+        .push_item(Function::new_trait_fn("nested"));
+
+    assert_eq!(
+        scope.try_to_string(),
+        Err(CodegenError::MissingFnBody { path: "a::run::nested".to_string() }),
+    );
+
+    let diags: Vec<String> = scope.validate().iter().map(ToString::to_string).collect();
+
+    assert_eq!(diags, [
+        "error: `a::run::Helper.type`: `type` is a reserved keyword",
+        "error: `a::run::Helper.one`: the field `one` is defined multiple times",
+    ]);
+}
+
+#[test]
+fn enum_discriminants() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let en = scope.new_enum("Level").repr("u8");
+    en.new_variant("Low").discriminant(1);
+    en.new_variant("High").discriminant("1 << 4");
+
+    let expected = format_code(r#"
+#[repr(u8)]
+enum Level {
+    Low = 1,
+    High = 1 << 4,
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    assert!(scope.validate().is_empty());
+    Ok(())
+}
+
+#[test]
+fn idents() {
+    assert_eq!(Ident::new("foo").unwrap().as_str(), "foo");
+    assert_eq!(Ident::new("type").unwrap().as_str(), "r#type");
+    assert_eq!(Ident::new("r#match").unwrap().as_str(), "r#match");
+    assert_eq!(Ident::new("async").unwrap().unraw(), "async");
+    assert!(Ident::new("async").unwrap().is_raw());
+    assert!(!Ident::new_with_edition("async", Edition::E2015).unwrap().is_raw());
+    assert!(Ident::new_with_edition("gen", Edition::E2024).unwrap().is_raw());
+
+    let err = Ident::new("self").unwrap_err();
+    assert_eq!(err.to_string(), "`self` can not be used as a raw identifier");
+    assert!(Ident::new("foo-bar").is_err());
+    assert!(Ident::new("_").is_err());
+
+    assert_eq!(Ident::sanitize("foo-bar").as_str(), "foo_bar");
+    assert_eq!(Ident::sanitize("123abc").as_str(), "_123abc");
+    assert_eq!(Ident::sanitize("self").as_str(), "self_");
+    assert_eq!(Ident::sanitize("match").as_str(), "r#match");
+    assert_eq!(Ident::sanitize("").as_str(), "_empty");
+    assert!(is_keyword("dyn", Edition::E2018));
+    assert!(!is_keyword("dyn", Edition::E2015));
+}
+
+#[test]
+fn sanitized_names() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.new_struct("Foo")
+        .field("type", "u8")
+        .field("foo-bar", "u8")
+        .field("123abc", "u8")
+        .sanitize_names(Edition::E2021);
+
+    let en = scope.new_enum("Bar");
+    en.new_variant("Self").named("self", "u8");
+    en.new_variant("2D");
+    en.sanitize_names(Edition::E2021);
+
+    let expected = format_code(r#"
+struct Foo {
+    r#type: u8,
+    foo_bar: u8,
+    _123abc: u8,
+}
+
+enum Bar {
+    Self_ { self_: u8 },
+    _2D,
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn case_conversion() {
+    assert_eq!(to_snake_case("HTTPServer2Config"), "http_server2_config");
+    assert_eq!(to_snake_case("fooBar-baz qux"), "foo_bar_baz_qux");
+    assert_eq!(to_snake_case("__privateField"), "__private_field");
+    assert_eq!(to_upper_camel_case("http_server"), "HttpServer");
+    assert_eq!(to_upper_camel_case("HTTPServer"), "HttpServer");
+    assert_eq!(to_upper_camel_case("vec3d"), "Vec3d");
+    assert_eq!(to_screaming_snake_case("maxValue"), "MAX_VALUE");
+    assert_eq!(Case::UpperCamel.convert("user-id"), "UserId");
+}
+
+#[test]
+fn name_allocator() -> io::Result<()> {
+    let mut scope = Scope::new();
+    let mut names = NameAllocator::new(Case::Snake);
+
+    assert_eq!(names.allocate("fooBar"), "foo_bar");
+    assert_eq!(names.allocate("foo-bar"), "foo_bar_2");
+    assert_eq!(names.allocate("fooBar"), "foo_bar");
+    assert_eq!(names.allocate("type"), "r#type");
+    assert_eq!(names.get("foo-bar"), Some("foo_bar_2"));
+    assert_eq!(names.original("foo_bar_2"), Some("foo-bar"));
+
+    let st = scope.new_struct("Foo");
+    for (_, name) in names.mapping() {
+        let mut field = Field::new_named(name, "u8");
+
+        if let Some(rename) = names.serde_rename(name) {
+            field.push_attr(rename);
+        }
+
+        st.push_field(field);
+    }
+
+    let mut fields = NameAllocator::from_struct(st, Case::Snake);
+    assert_eq!(fields.allocate("FooBar"), "foo_bar_3");
+
+    let mut types = NameAllocator::from_scope(&scope, Case::UpperCamel);
+    types.reserve("Foo2");
+    assert_eq!(types.allocate("foo"), "Foo3");
+
+    let expected = format_code(r#"
+struct Foo {
+    #[serde(rename = "fooBar")]
+    foo_bar: u8,
+    #[serde(rename = "foo-bar")]
+    foo_bar_2: u8,
+    r#type: u8,
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn expressions() {
+    let sum = Expr::path("a").binary(BinOp::Add, "b");
+    assert_eq!(sum.clone().binary(BinOp::Mul, "c").to_string(), "(a + b) * c");
+    assert_eq!(Expr::path("c").binary(BinOp::Sub, sum.clone()).to_string(), "c - (a + b)");
+    assert_eq!(sum.clone().method("pow", ["2"]).to_string(), "(a + b).pow(2)");
+    assert_eq!(Expr::unary(UnOp::Neg, sum.clone()).to_string(), "-(a + b)");
+    assert_eq!(Expr::path("x").cast("u8").binary(BinOp::Lt, "y").to_string(), "(x as u8) < y");
+    assert_eq!(Expr::path("x").field("f").call(None::<Expr>).to_string(), "(x.f)()");
+    assert_eq!(
+        Expr::path("x").binary(BinOp::AddAssign, Expr::path("y").binary(BinOp::Assign, "z")).to_string(),
+        "x += y = z",
+    );
+
+    let expr = Expr::path("client")
+        .method("get", [Expr::lit("/"), Expr::path("id").reference()])
+        .awaited()
+        .propagate()
+        .field("body")
+        .index(Expr::range(Some("1"), None::<Expr>, false));
+    assert_eq!(expr.to_string(), "client.get(\"/\", &id).await?.body[1..]");
+
+    let st = Expr::struct_lit("Foo", [("x", Expr::path("x")), ("y", Expr::tuple(["1"]))]);
+    assert_eq!(st.to_string(), "Foo { x, y: (1,) }");
+
+    let mac = Expr::macro_call("vec", Delimiter::Bracket, [Expr::array(["1", "2"])]);
+    assert_eq!(mac.to_string(), "vec![[1, 2]]");
+}
+
+#[test]
+fn expression_bodies() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.new_fn("total")
+        .arg("items", "&[u8]")
+        .ret("u32")
+        .push_expr(Expr::path("items")
+            .method("iter", None::<Expr>)
+            .method("map", [Expr::closure(["x"], Expr::unary(UnOp::Deref, "x").cast("u32"))])
+            .method("sum", None::<Expr>));
+
+    let expected = format_code(r#"
+fn total(items: &[u8]) -> u32 {
+    items.iter().map(|x| *x as u32).sum()
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn control_flow() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let mut chain = If::new(Expr::path("x").binary(BinOp::Gt, "0"));
+    chain.body().line("pos += 1;");
+    chain.new_else_if_let("Some(y)", "other").push_expr(Expr::path("handle").call(["y"]));
+    chain.new_else().line("neg += 1;");
+
+    let mut m = Match::new(Expr::struct_lit("Point", [("x", "0"), ("y", "1")]));
+    m.new_arm("Point { x: 0, .. }").guard("flag").block().line("zero();");
+    m.arm("_", Expr::macro_call("unreachable", Delimiter::Paren, None::<Expr>));
+
+    let mut inner = Loop::new_while_let("Some(item)", Expr::path("iter").method("next", None::<Expr>));
+    inner.body().push_expr(m);
+
+    let mut outer = Loop::new_for("x", Expr::range(Some("0"), Some("10"), false));
+    outer.label("'outer");
+    outer.body().push_expr(chain).push_expr(inner);
+    outer.body().push_expr(Expr::Continue(Some("'outer".to_string())));
+
+    let func = scope.new_fn("run");
+    func.new_let_else("Some(iter)", "source()").push_expr(Expr::Return(None));
+    func.push_expr(outer);
+
+    let mut block = Block::new_async(true);
+    block.push_expr(Expr::path("fut").awaited());
+    func.push_expr(Block::new_unsafe());
+    func.push_expr(Expr::path("tokio::spawn").call([block]));
+
+    let expected = format_code(r#"
+fn run() {
+    let Some(iter) = source() else {
+        return
+    };
+    'outer: for x in 0..10 {
+        if x > 0 {
+            pos += 1;
+        } else if let Some(y) = other {
+            handle(y)
+        } else {
+            neg += 1;
+        }
+        while let Some(item) = iter.next() {
+            match (Point { x: 0, y: 1 }) {
+                Point { x: 0, .. } if flag => {
+                    zero();
+                }
+                _ => unreachable!(),
+            }
+        }
+        continue 'outer
+    }
+    unsafe {
+    }
+    tokio::spawn(async move {
+        fut.await
+    })
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn patterns() -> io::Result<()> {
+    assert_eq!(Pat::bind("n", Pat::range(Some("1"), Some("5"), true)).to_string(), "n @ 1..=5");
+    assert_eq!(Pat::range(Some("1"), Some("5"), true).reference().to_string(), "&(1..=5)");
+    assert_eq!(Pat::ident_mut("x").reference().to_string(), "&(mut x)");
+    assert_eq!(Pat::tuple(["x"]).to_string(), "(x,)");
+    assert_eq!(Pat::slice([Pat::ident("first"), Pat::Rest]).to_string(), "[first, ..]");
+
+    let mut scope = Scope::new();
+
+    let mut m = Match::new("shape");
+    m.arm(Pat::struct_pat("Shape::Point", [("x", Pat::ident("x")), ("y", Pat::lit(0))], true), "x");
+    m.arm(Pat::tuple_struct("Shape::Line", [Pat::Wild, Pat::ident("len")]), "len");
+    m.arm(Pat::or([Pat::path("Shape::Empty"), Pat::path("Shape::Unknown")]), "0");
+
+    scope.new_fn("measure")
+        .arg(Pat::tuple([Pat::ident("shape"), Pat::Wild]), "(Shape, u8)")
+        .arg(Pat::ident_mut("count"), "u32")
+        .ret("u32")
+        .push_expr(m);
+
+    let expected = format_code(r#"
+fn measure((shape, _): (Shape, u8), mut count: u32) -> u32 {
+    match shape {
+        Shape::Point { x, y: 0, .. } => x,
+        Shape::Line(_, len) => len,
+        Shape::Empty | Shape::Unknown => 0,
+    }
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn statements() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let mut helper = Function::new("double");
+    helper.arg("x", "u32").ret("u32").push_expr(Expr::path("x").binary(BinOp::Mul, "2"));
+
+    let mut point = Struct::new("Point");
+    point.field("x", "u32");
+
+    let func = scope.new_fn("run");
+    func.ret("u32")
+        .push_item(point)
+        .push_item(helper)
+        .push_stmt(Stmt::Let {
+            pat: Pat::ident_mut("p"),
+            ty: Some(Type::new("Point")),
+            init: Some(Expr::struct_lit("Point", [("x", "1")])),
+        })
+        .push_stmt(Stmt::LetElse {
+            pat: "Some(step)".into(),
+            ty: Some(Type::new("Option<u32>")),
+            init: Expr::path("None"),
+            else_block: {
+                let mut block = Block::new("");
+                block.push_expr(Expr::ret(Expr::lit(0u32)));
+                block
+            },
+        })
+        .push_stmt(Stmt::semi(Expr::path("p.x").binary(BinOp::AddAssign, "step")))
+        .push_expr(Expr::path("double").call(["p.x"]));
+
+    func.insert_stmt(2, Stmt::Verbatim("// helpers above".to_string()));
+
+    assert_eq!(func.stmts().map(<[Stmt]>::len), Some(7));
+    assert!(matches!(func.stmts().unwrap()[2], Stmt::Verbatim(_)));
+
+    let expected = format_code(r#"
+fn run() -> u32 {
+    struct Point {
+        x: u32,
+    }
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+    // helpers above
+    let mut p: Point = Point { x: 1 };
+    let Some(step): Option<u32> = None else {
+        return 0;
+    };
+    p.x += step;
+    double(p.x)
+}"#)?;
+
+    let generated = format_code(scope.to_string())?;
+
+    assert_eq!(generated, expected);
+    Ok(())
+}
+
+#[test]
+fn literals() -> io::Result<()> {
+    assert_eq!(Lit::from("a\\b\t\u{7}").to_string(), r#""a\\b\t\u{7}""#);
+    assert_eq!(Lit::raw_str("plain").to_string(), r#"r"plain""#);
+    assert_eq!(Lit::raw_str("\"#").to_string(), "r##\"\"#\"##");
+    assert_eq!(Lit::byte_str(&b"\xff\"ok"[..]).to_string(), r#"b"\xff\"ok""#);
+    assert_eq!(Lit::c_str(c"hi\n").to_string(), r#"c"hi\n""#);
+    assert_eq!(Lit::byte(b'\'').to_string(), r"b'\''");
+    assert_eq!(Lit::from('"').to_string(), "'\"'");
+    assert_eq!(Lit::from(-5i8).suffixed().to_string(), "-5i8");
+    assert_eq!(Lit::from(u128::MAX).radix(Radix::Hex).to_string(), format!("{:#x}", u128::MAX));
+    assert_eq!(Lit::from(5u8).radix(Radix::Binary).to_string(), "0b101");
+    assert_eq!(Lit::from(2.0f32).suffixed().to_string(), "2.0f32");
+    assert_eq!(Lit::from(1e300).to_string(), "1e300");
+    assert_eq!(Lit::from(f64::NAN).suffixed().to_string(), "f64::NAN");
+    assert_eq!(Expr::lit(-1).method("abs", None::<Expr>).to_string(), "(-1).abs()");
+
+    let mut scope = Scope::new();
+
+    scope.new_module("data")
+        .new_const("GREETING", "&str")
+        .value(Lit::from("line \"one\"\nline two"));
+
+    scope.get_module_mut("data").unwrap()
+        .new_const("TEMPLATE", "&str")
+        .value(Lit::raw_str("{\n\"a\": 1\n}"));
+
+    let expected = "mod data {\n    const GREETING: &str = \"line \\\"one\\\"\\nline two\";\n\n    const TEMPLATE: &str = r#\"{\n\"a\": 1\n}\"#;\n}";
+
+    assert_eq!(scope.to_string(), expected);
+    Ok(())
+}
+
+#[test]
+fn generic_params() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.new_struct("Buf")
+        .push_generic(GenericParam::type_param("A").default("Global"))
+        .push_generic("const N: usize = 4")
+        .push_generic("T: Copy")
+        .push_lifetime("'b: 'a")
+        .push_lifetime("'a")
+        .field("data", "&'a [T; N]")
+        .field("alloc", "&'b A");
+
+    scope.new_enum("Either")
+        .extend_generics(["L", "R = L"])
+        .new_variant("Left").tuple("L");
+
+    scope.new_trait("Chunked")
+        .push_generic(GenericParam::const_param("N", "usize"))
+        .new_fn("chunk")
+        .arg_ref_self()
+        .ret("[u8; N]");
+
+    scope.new_impl("Buf<'a, 'b, T, N, A>")
+        .push_lifetime("'a")
+        .push_lifetime("'b")
+        .push_generic(GenericParam::type_param("T").bound("Copy").bound("'static"))
+        .push_generic("const N: usize")
+        .push_generic("A")
+        .new_fn("len")
+        .push_generic("const M: u8")
+        .arg_ref_self()
+        .ret("usize")
+        .line("N");
+
+    scope.new_type_alias("Small", "Buf<'static, 'static, u8, N>")
+        .push_generic("const N: usize = { 2 * 4 }");
+
+    let expected = format_code(r#"
+struct Buf<'b: 'a, 'a, T: Copy, A = Global, const N: usize = 4> {
+    data: &'a [T; N],
+    alloc: &'b A,
+}
+
+enum Either<L, R = L> {
+    Left(L),
+}
+
+trait Chunked<const N: usize> {
+    fn chunk(&self) -> [u8; N];
+}
+
+impl<'a, 'b, T: Copy + 'static, const N: usize, A> Buf<'a, 'b, T, N, A> {
+    fn len<const M: u8>(&self) -> usize {
+        N
+    }
+}
+
+type Small<const N: usize = { 2 * 4 }> = Buf<'static, 'static, u8, N>;"#)?;
+
+    assert_eq!(format_code(scope.to_string())?, expected);
+
+    let param = GenericParam::from("T: Clone + 'a = ()");
+    assert_eq!(param, GenericParam::type_param("T").bound("Clone").bound("'a").default("()"));
+    assert_eq!(GenericParam::from("'a: 'b + 'c").to_string(), "'a: 'b + 'c");
+    Ok(())
+}
+
+#[test]
+fn where_predicates() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    let source = scope.new_trait("Source")
+        .push_lifetime("'a")
+        .push_generic("T: ?Sized")
+        .parent("Send")
+        .parent(TypeBound::higher_ranked(["'de"], "Deserialize<'de>"))
+        .parent("'static")
+        .push_bound("'a: 'static")
+        .push_bound(WherePredicate::new("T").for_lifetime("'x").bound("Fn(&'x str)"))
+        .push_bound("<T as Iterator>::Item: Iterator<Item = u8>");
+
+    assert!(source.has_bounds());
+    assert_eq!(source.bound_count(), 3);
+
+    source.associated_type("Item")
+        .push_bound(TypeBound::maybe("Sized"))
+        .push_bound("Clone");
+
+    let mut bounds = Bounds::new();
+    bounds.push_bound(Bound::new_with_bound("'a", "'b"));
+    assert!(bounds.has_bounds());
+    assert_eq!(bounds.predicates(), [WherePredicate::lifetime("'a").outlives("'b")]);
+    assert!(!Bounds::new().has_bounds());
+
+    let expected = format_code(r#"
+trait Source<'a, T: ?Sized>: Send + for<'de> Deserialize<'de> + 'static
+where
+    'a: 'static,
+    for<'x> T: Fn(&'x str),
+    <T as Iterator>::Item: Iterator<Item = u8>,
+{
+    type Item: ?Sized + Clone;
+}"#)?;
+
+    assert_eq!(format_code(scope.to_string())?, expected);
+    Ok(())
+}
+
+#[test]
+fn function_signatures() -> io::Result<()> {
+    let mut scope = Scope::new();
+
+    scope.new_fn("len")
+        .set_vis(Vis::Pub)
+        .set_const(true)
+        .set_unsafe(true)
+        .extern_abi("C")
+        .arg(Pat::ident_mut("n"), "usize")
+        .ret("usize")
+        .line("n += 1;")
+        .line("n");
+
+    let imp = scope.new_impl("Reader<'a>");
+    imp.push_lifetime("'a");
+
+    imp.new_fn("peek")
+        .receiver(Receiver::Ref { lifetime: Some("'a".to_string()), mutable: false })
+        .ret("&'a [u8]")
+        .line("self.buf");
+
+    imp.new_fn("poll")
+        .receiver(Receiver::Typed {
+            mutable: true,
+            ty: "std::pin::Pin<&mut Self>".into(),
+        })
+        .line("self.as_mut();");
+
+    let consume = imp.new_fn("consume");
+    consume.receiver(Receiver::typed("Box<Self>"))
+        .set_async(true);
+    consume.new_arg("fd", "i32")
+        .push_attr("cfg(unix)");
+    consume.new_arg(Pat::ident_mut("_buf"), "Vec<u8>")
+        .push_attr("#[allow(unused_mut)]");
+
+    imp.new_fn("into_inner")
+        .receiver(Receiver::Value { mutable: true })
+        .ret("Self")
+        .line("self");
+
+    let expected = format_code(r#"
+pub const unsafe extern "C" fn len(mut n: usize) -> usize {
+    n += 1;
+    n
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&'a self) -> &'a [u8] {
+        self.buf
+    }
+
+    fn poll(mut self: std::pin::Pin<&mut Self>) {
+        self.as_mut();
+    }
+
+    async fn consume(self: Box<Self>, #[cfg(unix)] fd: i32, #[allow(unused_mut)] mut _buf: Vec<u8>) {}
+
+    fn into_inner(mut self) -> Self {
+        self
+    }
+}"#)?;
+
+    assert_eq!(format_code(scope.to_string())?, expected);
+    Ok(())
+}
+
+#[test]
+fn shared_signatures() -> io::Result<()> {
+    let mut sig = Signature::new("read_into");
+    sig.push_lifetime("'a")
+        .push_generic("B: AsMut<[u8]>")
+        .set_unsafe(true)
+        .arg_mut_self()
+        .arg("buf", "&'a mut B")
+        .ret("std::io::Result<usize>")
+        .push_bound("B: 'a");
+
+    let mut scope = Scope::new();
+
+    scope.new_trait("Reader")
+        .push_fn(Function::trait_fn_from_signature(sig.clone()));
+
+    let mut func = Function::from_signature(sig.clone());
+    func.line("todo!()");
+
+    scope.new_impl("File")
+        .impl_trait("Reader")
+        .push_fn(func.clone());
+
+    assert_eq!(func.signature(), &sig);
+    assert_eq!(func.signature().args()[0].pat(), &Pat::from("buf"));
+    assert_ne!(Function::new("read_into").signature(), &sig);
+
+    func.signature_mut().set_async(true);
+    assert_ne!(func.signature(), &sig);
+
+    let expected = format_code(r#"
+trait Reader {
+    unsafe fn read_into<'a, B: AsMut<[u8]>>(&mut self, buf: &'a mut B) -> std::io::Result<usize>
+    where
+        B: 'a;
+}
+
+impl Reader for File {
+    unsafe fn read_into<'a, B: AsMut<[u8]>>(&mut self, buf: &'a mut B) -> std::io::Result<usize>
+    where
+        B: 'a,
+    {
+        todo!()
+    }
+}"#)?;
+
+    assert_eq!(format_code(scope.to_string())?, expected);
+    Ok(())
+}
+
+#[test]
+fn trait_impl_skeletons() -> io::Result<()> {
+    let mut codec = Trait::new("Codec");
+    codec.push_lifetime("'a")
+        .push_generic("T: Clone")
+        .push_generic("const N: usize = 8")
+        .push_bound("T: 'a");
+
+    codec.associated_type("Error").push_bound("std::error::Error");
+    codec.new_const("NAME", "&'static str");
+    codec.new_const("LIMIT", "usize").value(Expr::lit(4));
+
+    codec.new_fn("encode")
+        .arg_ref_self()
+        .arg("value", "&'a T")
+        .ret("Result<[u8; N], Self::Error>");
+
+    codec.new_fn("reset").arg_mut_self();
+
+    codec.new_fn("describe")
+        .arg_ref_self()
+        .ret("&'static str")
+        .line("Self::NAME");
+
+    let mut scope = Scope::new();
+
+    scope.push_impl({
+        let mut imp = codec.impl_for("Json");
+        imp.associate_type("Error", "JsonError");
+        imp
+    });
+
+    scope.push_impl(codec.impl_for_with("Noop", |name| format!("Noop{}", name).into(), |func| {
+        if func.signature().get_ret().is_none() {
+            func.line("// nothing to do");
+        } else {
+            func.line("unreachable!()");
+        }
+    }));
+
+    let expected = format_code(r#"
+impl<'a, T: Clone, const N: usize> Codec<'a, T, N> for Json
+where
+    T: 'a,
+{
+    type Error = JsonError;
+    const NAME: &'static str = todo!();
+
+    fn encode(&self, value: &'a T) -> Result<[u8; N], Self::Error> {
+        todo!()
+    }
+
+    fn reset(&mut self) {
+        todo!()
+    }
+}
+
+impl<'a, T: Clone, const N: usize> Codec<'a, T, N> for Noop
+where
+    T: 'a,
+{
+    type Error = NoopError;
+    const NAME: &'static str = todo!();
+
+    fn encode(&self, value: &'a T) -> Result<[u8; N], Self::Error> {
+        unreachable!()
+    }
+
+    fn reset(&mut self) {
+        // nothing to do
+    }
+}"#)?;
+
+    assert_eq!(format_code(scope.to_string())?, expected);
+
+    let mut scope = Scope::new();
+    scope.push_impl(codec.impl_for("Json"));
+    assert!(scope.to_string().contains("type Error = todo!();"));
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_to_expr() -> io::Result<()> {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Meters(f32);
+
+    #[derive(Serialize)]
+    enum Shape {
+        Point,
+        Circle(Meters),
+        Rect { w: u8, h: u8 },
+    }
+
+    #[derive(Serialize)]
+    struct Config {
+        name: &'static str,
+        shapes: Vec<Shape>,
+        limit: Option<i32>,
+        pair: (char, bool),
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache: Option<u8>,
+    }
+
+    let config = Config {
+        name: "demo",
+        shapes: vec![Shape::Point, Shape::Circle(Meters(1.5)), Shape::Rect { w: 2, h: 3 }],
+        limit: Some(-4),
+        pair: ('x', true),
+        cache: None,
+    };
+
+    let mut scope = Scope::new();
+    scope.new_const("CONFIG", "Config")
+        .value(to_expr(&config).unwrap());
+
+    let expected = concat!(
+        "const CONFIG: Config = Config { name: \"demo\", shapes: &[Shape::Point, ",
+        "Shape::Circle(Meters(1.5)), Shape::Rect { w: 2, h: 3 }], limit: Some(-4), ",
+        "pair: ('x', true), ..Default::default() };",
+    );
+    assert_eq!(scope.to_string(), expected);
+
+    let map = BTreeMap::from([("a", 1u8), ("b", 2)]);
+
+    let mut ser = Serializer::new();
+    ser.seq_style(SeqStyle::Vec);
+    assert_eq!(ser.to_expr(&map).unwrap().to_string(), "vec![(\"a\", 1), (\"b\", 2)]");
+    assert_eq!(ser.to_expr(&()).unwrap().to_string(), "()");
+
+    ser.map_style(MapStyle::Phf);
+    assert_eq!(
+        ser.to_expr(&map).unwrap().to_string(),
+        "phf::phf_map! { \"a\" => 1, \"b\" => 2 }",
+    );
+    Ok(())
+}